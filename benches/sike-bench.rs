@@ -5,12 +5,13 @@ use rust_sike::{
     pke::{Message, PKE},
     sike_p434_params, sike_p503_params, sike_p610_params, sike_p751_params,
     strategy::*,
+    CurveIsogenies, PublicKey3, SecretKey,
 };
 
 pub fn bench_p434_pke_std(c: &mut Criterion) {
     let params = sike_p434_params(None, None).unwrap();
     let msg = Message::from_bytes(vec![0; params.clone().secparam / 8]);
-    let pke = PKE::setup(params);
+    let pke = PKE::setup(params).unwrap();
 
     let mut group = c.benchmark_group("SIKEp434 PKE (no opti)");
     let (sk, pk) = pke.gen().unwrap();
@@ -33,7 +34,7 @@ pub fn bench_p434_pke_optim(c: &mut Criterion) {
     )
     .unwrap();
     let msg = Message::from_bytes(vec![0; params.clone().secparam / 8]);
-    let pke = PKE::setup(params);
+    let pke = PKE::setup(params).unwrap();
 
     let mut group = c.benchmark_group("SIKEp434 PKE (opti)");
     let (sk, pk) = pke.gen().unwrap();
@@ -51,7 +52,7 @@ pub fn bench_p434_pke_optim(c: &mut Criterion) {
 
 pub fn bench_p434_kem_std(c: &mut Criterion) {
     let params = sike_p434_params(None, None).unwrap();
-    let kem = KEM::setup(params);
+    let kem = KEM::setup(params).unwrap();
 
     let mut group = c.benchmark_group("SIKEp434 KEM (no opti)");
     let (s, sk3, pk3) = kem.keygen().unwrap();
@@ -73,7 +74,7 @@ pub fn bench_p434_kem_optim(c: &mut Criterion) {
         Some(P434_THREE_TORSION_STRATEGY.to_vec()),
     )
     .unwrap();
-    let kem = KEM::setup(params);
+    let kem = KEM::setup(params).unwrap();
 
     let mut group = c.benchmark_group("SIKEp434 KEM (opti)");
     let (s, sk3, pk3) = kem.keygen().unwrap();
@@ -92,7 +93,7 @@ pub fn bench_p434_kem_optim(c: &mut Criterion) {
 pub fn bench_p503_pke_std(c: &mut Criterion) {
     let params = sike_p503_params(None, None).unwrap();
     let msg = Message::from_bytes(vec![0; params.clone().secparam / 8]);
-    let pke = PKE::setup(params);
+    let pke = PKE::setup(params).unwrap();
 
     let mut group = c.benchmark_group("SIKEp503 PKE (no opti)");
     let (sk, pk) = pke.gen().unwrap();
@@ -115,7 +116,7 @@ pub fn bench_p503_pke_optim(c: &mut Criterion) {
     )
     .unwrap();
     let msg = Message::from_bytes(vec![0; params.clone().secparam / 8]);
-    let pke = PKE::setup(params);
+    let pke = PKE::setup(params).unwrap();
 
     let mut group = c.benchmark_group("SIKEp503 PKE (opti)");
     let (sk, pk) = pke.gen().unwrap();
@@ -133,7 +134,7 @@ pub fn bench_p503_pke_optim(c: &mut Criterion) {
 
 pub fn bench_p503_kem_std(c: &mut Criterion) {
     let params = sike_p503_params(None, None).unwrap();
-    let kem = KEM::setup(params);
+    let kem = KEM::setup(params).unwrap();
 
     let mut group = c.benchmark_group("SIKEp503 KEM (no opti)");
     let (s, sk3, pk3) = kem.keygen().unwrap();
@@ -155,7 +156,7 @@ pub fn bench_p503_kem_optim(c: &mut Criterion) {
         Some(P503_THREE_TORSION_STRATEGY.to_vec()),
     )
     .unwrap();
-    let kem = KEM::setup(params);
+    let kem = KEM::setup(params).unwrap();
 
     let mut group = c.benchmark_group("SIKEp503 KEM (opti)");
     let (s, sk3, pk3) = kem.keygen().unwrap();
@@ -174,7 +175,7 @@ pub fn bench_p503_kem_optim(c: &mut Criterion) {
 pub fn bench_p610_pke_std(c: &mut Criterion) {
     let params = sike_p610_params(None, None).unwrap();
     let msg = Message::from_bytes(vec![0; params.clone().secparam / 8]);
-    let pke = PKE::setup(params);
+    let pke = PKE::setup(params).unwrap();
 
     let mut group = c.benchmark_group("SIKEp610 PKE (no opti)");
     let (sk, pk) = pke.gen().unwrap();
@@ -197,7 +198,7 @@ pub fn bench_p610_pke_optim(c: &mut Criterion) {
     )
     .unwrap();
     let msg = Message::from_bytes(vec![0; params.clone().secparam / 8]);
-    let pke = PKE::setup(params);
+    let pke = PKE::setup(params).unwrap();
 
     let mut group = c.benchmark_group("SIKEp610 PKE (opti)");
     let (sk, pk) = pke.gen().unwrap();
@@ -215,7 +216,7 @@ pub fn bench_p610_pke_optim(c: &mut Criterion) {
 
 pub fn bench_p610_kem_std(c: &mut Criterion) {
     let params = sike_p610_params(None, None).unwrap();
-    let kem = KEM::setup(params);
+    let kem = KEM::setup(params).unwrap();
 
     let mut group = c.benchmark_group("SIKEp610 KEM (no opti)");
     let (s, sk3, pk3) = kem.keygen().unwrap();
@@ -237,7 +238,7 @@ pub fn bench_p610_kem_optim(c: &mut Criterion) {
         Some(P610_THREE_TORSION_STRATEGY.to_vec()),
     )
     .unwrap();
-    let kem = KEM::setup(params);
+    let kem = KEM::setup(params).unwrap();
 
     let mut group = c.benchmark_group("SIKEp610 KEM (opti)");
     let (s, sk3, pk3) = kem.keygen().unwrap();
@@ -256,7 +257,7 @@ pub fn bench_p610_kem_optim(c: &mut Criterion) {
 pub fn bench_p751_pke_std(c: &mut Criterion) {
     let params = sike_p751_params(None, None).unwrap();
     let msg = Message::from_bytes(vec![0; params.clone().secparam / 8]);
-    let pke = PKE::setup(params);
+    let pke = PKE::setup(params).unwrap();
 
     let mut group = c.benchmark_group("SIKEp751 PKE (no opti)");
     let (sk, pk) = pke.gen().unwrap();
@@ -279,7 +280,7 @@ pub fn bench_p751_pke_optim(c: &mut Criterion) {
     )
     .unwrap();
     let msg = Message::from_bytes(vec![0; params.clone().secparam / 8]);
-    let pke = PKE::setup(params);
+    let pke = PKE::setup(params).unwrap();
 
     let mut group = c.benchmark_group("SIKEp751 PKE (opti)");
     let (sk, pk) = pke.gen().unwrap();
@@ -297,7 +298,7 @@ pub fn bench_p751_pke_optim(c: &mut Criterion) {
 
 pub fn bench_p751_kem_std(c: &mut Criterion) {
     let params = sike_p751_params(None, None).unwrap();
-    let kem = KEM::setup(params);
+    let kem = KEM::setup(params).unwrap();
 
     let mut group = c.benchmark_group("SIKEp751 KEM (no opti)");
     let (s, sk3, pk3) = kem.keygen().unwrap();
@@ -319,7 +320,7 @@ pub fn bench_p751_kem_optim(c: &mut Criterion) {
         Some(P751_THREE_TORSION_STRATEGY.to_vec()),
     )
     .unwrap();
-    let kem = KEM::setup(params);
+    let kem = KEM::setup(params).unwrap();
 
     let mut group = c.benchmark_group("SIKEp751 KEM (opti)");
     let (s, sk3, pk3) = kem.keygen().unwrap();
@@ -335,6 +336,277 @@ pub fn bench_p751_kem_optim(c: &mut Criterion) {
     group.finish();
 }
 
+/// Walk on the 2-torsion with and without the strategy, as `CurveIsogenies::benchmark_walk`:
+/// the strategy is expected to be the faster one
+pub fn bench_p434_keygen(c: &mut Criterion) {
+    let simple = sike_p434_params(None, None).unwrap();
+    let optim = sike_p434_params(Some(P434_TWO_TORSION_STRATEGY.to_vec()), None).unwrap();
+    let sk2 = SecretKey::get_random_secret_key(simple.keyspace2 as usize).unwrap();
+    let simple = CurveIsogenies::init(simple);
+    let optim = CurveIsogenies::init(optim);
+
+    let mut group = c.benchmark_group("SIKEp434 keygen");
+    group.bench_function("isogen2 (no opti)", |b| b.iter(|| simple.isogen2(&sk2)));
+    group.bench_function("isogen2 (opti)", |b| b.iter(|| optim.isogen2(&sk2)));
+
+    group.finish();
+}
+
+/// Key generation on the 2-torsion, the side whose walk doubles (`KEM::keygen` triples)
+pub fn bench_p751_keygen(c: &mut Criterion) {
+    let simple = sike_p751_params(None, None).unwrap();
+    let optim = sike_p751_params(
+        Some(P751_TWO_TORSION_STRATEGY.to_vec()),
+        Some(P751_THREE_TORSION_STRATEGY.to_vec()),
+    )
+    .unwrap();
+    let sk2 = SecretKey::get_random_secret_key(simple.keyspace2 as usize).unwrap();
+    let simple = CurveIsogenies::init(simple);
+    let optim = CurveIsogenies::init(optim);
+
+    let mut group = c.benchmark_group("SIKEp751 keygen");
+    group.bench_function("isogen2 (no opti)", |b| b.iter(|| simple.isogen2(&sk2)));
+    group.bench_function("isogen2 (opti)", |b| b.iter(|| optim.isogen2(&sk2)));
+
+    group.finish();
+}
+
+pub fn bench_p434_isogenies_std(c: &mut Criterion) {
+    let params = sike_p434_params(None, None).unwrap();
+    let nks2 = params.keyspace2 as usize;
+    let nks3 = params.keyspace3 as usize;
+    let iso = CurveIsogenies::init(params);
+
+    let mut group = c.benchmark_group("SIKEp434 isogenies (no opti)");
+    let sk2 = SecretKey::get_random_secret_key(nks2).unwrap();
+    let sk3 = SecretKey::get_random_secret_key(nks3).unwrap();
+    let pk2 = iso.isogen2(&sk2).unwrap();
+    let pk3 = iso.isogen3(&sk3).unwrap();
+
+    group.bench_function("isogen2", |b| b.iter(|| iso.isogen2(&sk2)));
+    group.bench_function("isogen3", |b| b.iter(|| iso.isogen3(&sk3)));
+    group.bench_function("isoex2", |b| b.iter(|| iso.isoex2(&sk2, &pk3)));
+    group.bench_function("isoex3", |b| b.iter(|| iso.isoex3(&sk3, &pk2)));
+
+    group.finish();
+}
+
+pub fn bench_p434_isogenies_optim(c: &mut Criterion) {
+    let params = sike_p434_params(
+        Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+        Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+    )
+    .unwrap();
+    let nks2 = params.keyspace2 as usize;
+    let nks3 = params.keyspace3 as usize;
+    let iso = CurveIsogenies::init(params);
+
+    let mut group = c.benchmark_group("SIKEp434 isogenies (opti)");
+    let sk2 = SecretKey::get_random_secret_key(nks2).unwrap();
+    let sk3 = SecretKey::get_random_secret_key(nks3).unwrap();
+    let pk2 = iso.isogen2(&sk2).unwrap();
+    let pk3 = iso.isogen3(&sk3).unwrap();
+    let pk2_projective = iso.isogen2_projective(&sk2).unwrap();
+
+    group.bench_function("isogen2", |b| b.iter(|| iso.isogen2(&sk2)));
+    group.bench_function("normalisation", |b| b.iter(|| pk2_projective.to_affine()));
+    group.bench_function("normalisation (unbatched)", |b| {
+        b.iter(|| pk2_projective.to_affine_unbatched())
+    });
+    group.bench_function("isogen3", |b| b.iter(|| iso.isogen3(&sk3)));
+    group.bench_function("isoex2", |b| b.iter(|| iso.isoex2(&sk2, &pk3)));
+    group.bench_function("isoex3", |b| b.iter(|| iso.isoex3(&sk3, &pk2)));
+
+    group.finish();
+}
+
+pub fn bench_p503_isogenies_std(c: &mut Criterion) {
+    let params = sike_p503_params(None, None).unwrap();
+    let nks2 = params.keyspace2 as usize;
+    let nks3 = params.keyspace3 as usize;
+    let iso = CurveIsogenies::init(params);
+
+    let mut group = c.benchmark_group("SIKEp503 isogenies (no opti)");
+    let sk2 = SecretKey::get_random_secret_key(nks2).unwrap();
+    let sk3 = SecretKey::get_random_secret_key(nks3).unwrap();
+    let pk2 = iso.isogen2(&sk2).unwrap();
+    let pk3 = iso.isogen3(&sk3).unwrap();
+
+    group.bench_function("isogen2", |b| b.iter(|| iso.isogen2(&sk2)));
+    group.bench_function("isogen3", |b| b.iter(|| iso.isogen3(&sk3)));
+    group.bench_function("isoex2", |b| b.iter(|| iso.isoex2(&sk2, &pk3)));
+    group.bench_function("isoex3", |b| b.iter(|| iso.isoex3(&sk3, &pk2)));
+
+    group.finish();
+}
+
+pub fn bench_p503_isogenies_optim(c: &mut Criterion) {
+    let params = sike_p503_params(
+        Some(P503_TWO_TORSION_STRATEGY.to_vec()),
+        Some(P503_THREE_TORSION_STRATEGY.to_vec()),
+    )
+    .unwrap();
+    let nks2 = params.keyspace2 as usize;
+    let nks3 = params.keyspace3 as usize;
+    let iso = CurveIsogenies::init(params);
+
+    let mut group = c.benchmark_group("SIKEp503 isogenies (opti)");
+    let sk2 = SecretKey::get_random_secret_key(nks2).unwrap();
+    let sk3 = SecretKey::get_random_secret_key(nks3).unwrap();
+    let pk2 = iso.isogen2(&sk2).unwrap();
+    let pk3 = iso.isogen3(&sk3).unwrap();
+
+    group.bench_function("isogen2", |b| b.iter(|| iso.isogen2(&sk2)));
+    group.bench_function("isogen3", |b| b.iter(|| iso.isogen3(&sk3)));
+    group.bench_function("isoex2", |b| b.iter(|| iso.isoex2(&sk2, &pk3)));
+    group.bench_function("isoex3", |b| b.iter(|| iso.isoex3(&sk3, &pk2)));
+
+    group.finish();
+}
+
+pub fn bench_p610_isogenies_std(c: &mut Criterion) {
+    let params = sike_p610_params(None, None).unwrap();
+    let nks2 = params.keyspace2 as usize;
+    let nks3 = params.keyspace3 as usize;
+    let iso = CurveIsogenies::init(params);
+
+    let mut group = c.benchmark_group("SIKEp610 isogenies (no opti)");
+    let sk2 = SecretKey::get_random_secret_key(nks2).unwrap();
+    let sk3 = SecretKey::get_random_secret_key(nks3).unwrap();
+    let pk2 = iso.isogen2(&sk2).unwrap();
+    let pk3 = iso.isogen3(&sk3).unwrap();
+
+    group.bench_function("isogen2", |b| b.iter(|| iso.isogen2(&sk2)));
+    group.bench_function("isogen3", |b| b.iter(|| iso.isogen3(&sk3)));
+    group.bench_function("isoex2", |b| b.iter(|| iso.isoex2(&sk2, &pk3)));
+    group.bench_function("isoex3", |b| b.iter(|| iso.isoex3(&sk3, &pk2)));
+
+    group.finish();
+}
+
+pub fn bench_p610_isogenies_optim(c: &mut Criterion) {
+    let params = sike_p610_params(
+        Some(P610_TWO_TORSION_STRATEGY.to_vec()),
+        Some(P610_THREE_TORSION_STRATEGY.to_vec()),
+    )
+    .unwrap();
+    let nks2 = params.keyspace2 as usize;
+    let nks3 = params.keyspace3 as usize;
+    let iso = CurveIsogenies::init(params);
+
+    let mut group = c.benchmark_group("SIKEp610 isogenies (opti)");
+    let sk2 = SecretKey::get_random_secret_key(nks2).unwrap();
+    let sk3 = SecretKey::get_random_secret_key(nks3).unwrap();
+    let pk2 = iso.isogen2(&sk2).unwrap();
+    let pk3 = iso.isogen3(&sk3).unwrap();
+
+    group.bench_function("isogen2", |b| b.iter(|| iso.isogen2(&sk2)));
+    group.bench_function("isogen3", |b| b.iter(|| iso.isogen3(&sk3)));
+    group.bench_function("isoex2", |b| b.iter(|| iso.isoex2(&sk2, &pk3)));
+    group.bench_function("isoex3", |b| b.iter(|| iso.isoex3(&sk3, &pk2)));
+
+    group.finish();
+}
+
+pub fn bench_p751_isogenies_std(c: &mut Criterion) {
+    let params = sike_p751_params(None, None).unwrap();
+    let nks2 = params.keyspace2 as usize;
+    let nks3 = params.keyspace3 as usize;
+    let iso = CurveIsogenies::init(params);
+
+    let mut group = c.benchmark_group("SIKEp751 isogenies (no opti)");
+    let sk2 = SecretKey::get_random_secret_key(nks2).unwrap();
+    let sk3 = SecretKey::get_random_secret_key(nks3).unwrap();
+    let pk2 = iso.isogen2(&sk2).unwrap();
+    let pk3 = iso.isogen3(&sk3).unwrap();
+
+    group.bench_function("isogen2", |b| b.iter(|| iso.isogen2(&sk2)));
+    group.bench_function("isogen3", |b| b.iter(|| iso.isogen3(&sk3)));
+    group.bench_function("isoex2", |b| b.iter(|| iso.isoex2(&sk2, &pk3)));
+    group.bench_function("isoex3", |b| b.iter(|| iso.isoex3(&sk3, &pk2)));
+
+    group.finish();
+}
+
+pub fn bench_p751_isogenies_optim(c: &mut Criterion) {
+    let params = sike_p751_params(
+        Some(P751_TWO_TORSION_STRATEGY.to_vec()),
+        Some(P751_THREE_TORSION_STRATEGY.to_vec()),
+    )
+    .unwrap();
+    let nks2 = params.keyspace2 as usize;
+    let nks3 = params.keyspace3 as usize;
+    let iso = CurveIsogenies::init(params);
+
+    let mut group = c.benchmark_group("SIKEp751 isogenies (opti)");
+    let sk2 = SecretKey::get_random_secret_key(nks2).unwrap();
+    let sk3 = SecretKey::get_random_secret_key(nks3).unwrap();
+    let pk2 = iso.isogen2(&sk2).unwrap();
+    let pk3 = iso.isogen3(&sk3).unwrap();
+
+    group.bench_function("isogen2", |b| b.iter(|| iso.isogen2(&sk2)));
+    group.bench_function("isogen3", |b| b.iter(|| iso.isogen3(&sk3)));
+    group.bench_function("isoex2", |b| b.iter(|| iso.isoex2(&sk2, &pk3)));
+    group.bench_function("isoex3", |b| b.iter(|| iso.isoex3(&sk3, &pk2)));
+
+    group.finish();
+}
+
+/// `PKE::xor` for the field of `pke`, which is not nameable here
+fn pke_xor<K, H>(_pke: &PKE<K, H>, input1: &[u8], input2: &[u8]) -> Vec<u8> {
+    PKE::<K, H>::xor(input1, input2)
+}
+
+/// Symmetric part of the PKE, F and the XOR of the mask, against the isogeny exchange that
+/// dominates encryption and decryption
+pub fn bench_p434_pke_symmetric(c: &mut Criterion) {
+    let params = sike_p434_params(
+        Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+        Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+    )
+    .unwrap();
+    let msg = Message::from_bytes(vec![0; params.secparam / 8]);
+    let nks2 = params.keyspace2 as usize;
+    let pke = PKE::setup(params).unwrap();
+
+    let mut group = c.benchmark_group("SIKEp434 PKE symmetric overhead");
+    let (_, pk) = pke.gen().unwrap();
+    let pk = PublicKey3::new(pk);
+    let sk2 = SecretKey::get_random_secret_key(nks2).unwrap();
+    let j = pke.isogenies.isoex2(&sk2, &pk).unwrap();
+
+    let mask = pke.hash_function_f(j.clone()).unwrap();
+
+    group.bench_function("F", |b| b.iter(|| pke.hash_function_f(j.clone())));
+    group.bench_function("xor", |b| b.iter(|| pke_xor(&pke, &msg.bytes, &mask)));
+    group.bench_function("isoex2", |b| b.iter(|| pke.isogenies.isoex2(&sk2, &pk)));
+
+    group.finish();
+}
+
+#[cfg(feature = "unsafe_profiling")]
+pub fn bench_p434_kem_fo_overhead(c: &mut Criterion) {
+    let params = sike_p434_params(
+        Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+        Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+    )
+    .unwrap();
+    let kem = KEM::setup(params).unwrap();
+
+    let mut group = c.benchmark_group("SIKEp434 KEM FO overhead");
+    let (s, sk3, pk3) = kem.keygen().unwrap();
+    let (c, _k) = kem.encaps(&pk3).unwrap();
+
+    group.bench_function("Decapsulation", |b| {
+        b.iter(|| kem.decaps(&s, &sk3, &pk3, c.clone()))
+    });
+    group.bench_function("Decapsulation (no re-encryption)", |b| {
+        b.iter(|| kem.decaps_no_reencrypt(&sk3, c.clone()))
+    });
+
+    group.finish();
+}
+
 pub fn config() -> Criterion {
     Criterion::default().sample_size(10)
 }
@@ -373,4 +645,35 @@ criterion_group! {
     targets = bench_p434_kem_optim, bench_p503_kem_optim, bench_p610_kem_optim, bench_p751_kem_optim
 }
 
-criterion_main!(kem);
+criterion_group! {
+    name = keygen;
+    config = config();
+    targets = bench_p434_keygen, bench_p751_keygen
+}
+
+criterion_group! {
+    name = symmetric;
+    config = config();
+    targets = bench_p434_pke_symmetric
+}
+
+criterion_group! {
+    name = isogenies;
+    config = config();
+    targets = bench_p434_isogenies_std, bench_p434_isogenies_optim, bench_p503_isogenies_std,
+        bench_p503_isogenies_optim, bench_p610_isogenies_std, bench_p610_isogenies_optim,
+        bench_p751_isogenies_std, bench_p751_isogenies_optim
+}
+
+#[cfg(feature = "unsafe_profiling")]
+criterion_group! {
+    name = fo;
+    config = config();
+    targets = bench_p434_kem_fo_overhead
+}
+
+#[cfg(not(feature = "unsafe_profiling"))]
+criterion_main!(kem, keygen, isogenies, symmetric);
+
+#[cfg(feature = "unsafe_profiling")]
+criterion_main!(kem, keygen, isogenies, symmetric, fo);
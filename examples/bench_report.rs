@@ -0,0 +1,129 @@
+//! Times keygen, encaps and decaps of the KEM and prints the mean durations as JSON
+//!
+//! `cargo run --release --example bench_report -- [iterations] [p434|p503|p610|p751 ...]`
+//!
+//! Prints one object such as `{"p434_keygen_us": 1234, ...}`, with all the parameter sets
+//! when none is given. Unlike the criterion benches, this is a single timed loop per
+//! operation, meant for dashboards rather than statistics.
+
+use rust_sike::{
+    kem::KEM,
+    sike_p434_params, sike_p503_params, sike_p610_params, sike_p751_params,
+    strategy::{
+        P434_THREE_TORSION_STRATEGY, P434_TWO_TORSION_STRATEGY, P503_THREE_TORSION_STRATEGY,
+        P503_TWO_TORSION_STRATEGY, P610_THREE_TORSION_STRATEGY, P610_TWO_TORSION_STRATEGY,
+        P751_THREE_TORSION_STRATEGY, P751_TWO_TORSION_STRATEGY,
+    },
+};
+use std::time::Instant;
+
+const SETS: [&str; 4] = ["p434", "p503", "p610", "p751"];
+
+/// Mean duration of `f` over `iterations` calls, in microseconds
+fn mean_us<T>(iterations: u32, mut f: impl FnMut() -> T) -> u128 {
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    start.elapsed().as_micros() / u128::from(iterations)
+}
+
+macro_rules! time_kem {
+    ($params:ident, $two:ident, $three:ident, $iterations:expr) => {{
+        let params = $params(Some($two.to_vec()), Some($three.to_vec())).unwrap();
+        let kem = KEM::setup(params).unwrap();
+        let (s, sk3, pk3) = kem.keygen().unwrap();
+        let (c, _) = kem.encaps(&pk3).unwrap();
+
+        [
+            ("keygen", mean_us($iterations, || kem.keygen().unwrap())),
+            ("encaps", mean_us($iterations, || kem.encaps(&pk3).unwrap())),
+            (
+                "decaps",
+                mean_us($iterations, || {
+                    kem.decaps(&s, &sk3, &pk3, c.clone()).unwrap()
+                }),
+            ),
+        ]
+    }};
+}
+
+/// Times the operations of the parameter set `set`
+fn time_set(set: &str, iterations: u32) -> [(&'static str, u128); 3] {
+    match set {
+        "p434" => time_kem!(
+            sike_p434_params,
+            P434_TWO_TORSION_STRATEGY,
+            P434_THREE_TORSION_STRATEGY,
+            iterations
+        ),
+        "p503" => time_kem!(
+            sike_p503_params,
+            P503_TWO_TORSION_STRATEGY,
+            P503_THREE_TORSION_STRATEGY,
+            iterations
+        ),
+        "p610" => time_kem!(
+            sike_p610_params,
+            P610_TWO_TORSION_STRATEGY,
+            P610_THREE_TORSION_STRATEGY,
+            iterations
+        ),
+        "p751" => time_kem!(
+            sike_p751_params,
+            P751_TWO_TORSION_STRATEGY,
+            P751_THREE_TORSION_STRATEGY,
+            iterations
+        ),
+        _ => panic!("unknown parameter set {}, expected one of {:?}", set, SETS),
+    }
+}
+
+/// JSON object with the mean durations of each operation of each set
+fn report(sets: &[&str], iterations: u32) -> String {
+    let fields: Vec<String> = sets
+        .iter()
+        .flat_map(|set| {
+            time_set(set, iterations)
+                .iter()
+                .map(|(op, us)| format!("\"{}_{}_us\": {}", set, op, us))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    format!("{{{}}}", fields.join(", "))
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let iterations = args
+        .next()
+        .map(|n| n.parse().expect("iterations must be a positive integer"))
+        .unwrap_or(10);
+    assert!(iterations > 0, "iterations must be a positive integer");
+
+    let sets: Vec<String> = args.collect();
+    let sets: Vec<&str> = if sets.is_empty() {
+        SETS.to_vec()
+    } else {
+        sets.iter().map(String::as_str).collect()
+    };
+
+    println!("{}", report(&sets, iterations));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_p434() {
+        let json: serde_json::Value = serde_json::from_str(&report(&["p434"], 1)).unwrap();
+        let object = json.as_object().unwrap();
+
+        assert_eq!(object.len(), 3);
+        for op in &["keygen", "encaps", "decaps"] {
+            assert!(object[&format!("p434_{}_us", op)].is_u64());
+        }
+    }
+}
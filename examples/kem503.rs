@@ -10,7 +10,7 @@ fn main() {
         Some(P503_THREE_TORSION_STRATEGY.to_vec()),
     )
     .unwrap();
-    let kem = KEM::setup(params);
+    let kem = KEM::setup(params).unwrap();
 
     let (s, sk3, pk3) = kem.keygen().unwrap();
     let (c, _k) = kem.encaps(&pk3).unwrap();
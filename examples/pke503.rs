@@ -11,7 +11,7 @@ fn main() {
     )
     .unwrap();
     let msg = Message::from_bytes(vec![0; params.clone().secparam / 8]);
-    let pke = PKE::setup(params);
+    let pke = PKE::setup(params).unwrap();
 
     let (sk, pk) = pke.gen().unwrap();
     let ciphertext = pke.enc(&pk, msg.clone()).unwrap();
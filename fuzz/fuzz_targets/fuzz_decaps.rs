@@ -0,0 +1,15 @@
+//! Decapsulation of arbitrary ciphertexts must fail cleanly, never panic
+//!
+//! Run with `cargo fuzz run fuzz_decaps corpus/fuzz_decaps` from this directory, the seed
+//! corpus is regenerated by `cargo run --release --bin gen_corpus`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rust_sike_fuzz::{decode, FIXTURE};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(k) = (FIXTURE.decaps)(decode(data)) {
+        assert_eq!(k.len(), FIXTURE.shared_secret_len);
+    }
+});
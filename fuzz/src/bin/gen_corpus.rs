@@ -0,0 +1,25 @@
+//! Writes valid ciphertexts for the fixed keypair to `corpus/fuzz_decaps`
+
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
+use rust_sike_fuzz::{encode, FIXTURE};
+
+use std::fs;
+
+/// Number of ciphertexts in the seed corpus
+const SEEDS: u8 = 4;
+
+fn main() {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/corpus/fuzz_decaps");
+    fs::create_dir_all(dir).unwrap();
+
+    for i in 0..SEEDS {
+        let mut rng = ChaCha20Rng::from_seed([i + 1; 32]);
+        let (c, k) = (FIXTURE.encaps)(&mut rng).unwrap();
+        assert_eq!((FIXTURE.decaps)(c.clone()).unwrap(), k);
+
+        let data = encode(&c);
+        assert_eq!(encode(&rust_sike_fuzz::decode(&data)), data);
+        fs::write(format!("{}/seed_{}", dir, i), data).unwrap();
+    }
+}
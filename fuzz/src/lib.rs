@@ -0,0 +1,91 @@
+//! Shared fixture of the fuzz targets
+//!
+//! Inputs are framed as three length bytes, followed by the three parts of `c0` and by `c1`:
+//! `l00 ‖ l01 ‖ l02 ‖ bytes00 ‖ bytes01 ‖ bytes02 ‖ bytes1`. Any buffer decodes to a
+//! ciphertext, missing bytes giving shorter parts.
+
+use once_cell::sync::Lazy;
+use rand_chacha::ChaCha20Rng;
+use rand_core::SeedableRng;
+use rust_sike::{pke::Ciphertext, sike_p434_params, strategy::*, SikeError, KEM};
+
+use std::sync::Arc;
+
+/// Result of an encapsulation: ciphertext and shared secret
+pub type Encaps = Result<(Ciphertext, Vec<u8>), SikeError>;
+
+/// Result of a decapsulation: shared secret
+pub type Decaps = Result<Vec<u8>, SikeError>;
+
+/// KEM and keypair derived from a fixed seed, so that the seed corpus stays valid
+///
+/// The field types of the crate cannot be named outside of it, so the operations are kept
+/// as closures over the KEM and the keypair.
+pub struct Fixture {
+    /// Encapsulates for the fixed public key, with randomness drawn from the generator
+    pub encaps: Box<dyn Fn(&mut ChaCha20Rng) -> Encaps + Send + Sync>,
+
+    /// Decapsulates with the fixed keypair
+    pub decaps: Box<dyn Fn(Ciphertext) -> Decaps + Send + Sync>,
+
+    /// Size in bytes of the shared secrets
+    pub shared_secret_len: usize,
+}
+
+/// Fixture shared by all the runs of a target
+pub static FIXTURE: Lazy<Fixture> = Lazy::new(|| {
+    let params = sike_p434_params(
+        Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+        Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+    )
+    .unwrap();
+    let shared_secret_len = params.secparam / 8;
+    let kem = Arc::new(KEM::setup(params).unwrap());
+
+    let mut rng = ChaCha20Rng::from_seed([0; 32]);
+    let (s, sk, pk) = kem.keygen_with_rng(&mut rng).unwrap();
+    let pk = Arc::new(pk);
+
+    let encaps = {
+        let (kem, pk) = (kem.clone(), pk.clone());
+        Box::new(move |rng: &mut ChaCha20Rng| kem.encaps_with_rng(&pk, rng))
+    };
+    let decaps = Box::new(move |c: Ciphertext| kem.decaps(&s, &sk, &pk, c));
+
+    Fixture {
+        encaps,
+        decaps,
+        shared_secret_len,
+    }
+});
+
+/// Decodes a framed buffer into a ciphertext
+pub fn decode(data: &[u8]) -> Ciphertext {
+    let (lens, mut rest) = data.split_at(data.len().min(3));
+    let mut take = |i: usize| {
+        let len = lens.get(i).map_or(0, |&l| l as usize).min(rest.len());
+        let (part, tail) = rest.split_at(len);
+        rest = tail;
+        part.to_vec()
+    };
+
+    Ciphertext {
+        bytes00: take(0),
+        bytes01: take(1),
+        bytes02: take(2),
+        bytes1: rest.to_vec(),
+    }
+}
+
+/// Frames a ciphertext, the inverse of `decode`
+pub fn encode(c: &Ciphertext) -> Vec<u8> {
+    let len = |part: &[u8]| part.len() as u8;
+    [
+        &[len(&c.bytes00), len(&c.bytes01), len(&c.bytes02)][..],
+        &c.bytes00,
+        &c.bytes01,
+        &c.bytes02,
+        &c.bytes1,
+    ]
+    .concat()
+}
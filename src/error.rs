@@ -0,0 +1,139 @@
+//! Error type
+
+use crate::isogeny::SikeParameterSet;
+
+use thiserror::Error;
+
+/// Errors returned by the SIKE primitives
+#[derive(Debug, Error)]
+pub enum SikeError {
+    /// The random number generator failed
+    #[error("RNG error")]
+    Rng(#[from] getrandom::Error),
+
+    /// A field element could not be parsed
+    #[error("cannot parse field element")]
+    ParseFieldElement(#[from] rug::integer::ParseIntegerError),
+
+    /// An integer constant could not be parsed
+    #[error("cannot parse integer")]
+    ParseInt(#[from] std::num::ParseIntError),
+
+    /// A field element has no multiplicative inverse
+    #[error("cannot invert")]
+    NotInvertible,
+
+    /// A byte representation is malformed
+    #[error("invalid encoding: {0}")]
+    InvalidEncoding(&'static str),
+
+    /// A message cannot be encrypted as is
+    #[error("invalid message: {0}")]
+    InvalidMessage(&'static str),
+
+    /// The public key does not define a valid curve
+    #[error("incorrect public key")]
+    InvalidPublicKey,
+
+    /// The tree-traversal strategy does not match the parameters
+    #[error("invalid strategy")]
+    InvalidStrategy,
+
+    /// The tree-traversal strategy does not have one entry per isogeny step but the last
+    #[error("invalid strategy length: {len} entries, expected {expected}")]
+    InvalidStrategyLength {
+        /// Length of the strategy
+        len: usize,
+        /// Length expected by the parameters
+        expected: usize,
+    },
+
+    /// The security parameter is not a multiple of 8
+    #[error("invalid security parameter: must be a multiple of 8")]
+    InvalidSecparam,
+
+    /// The key spaces do not match the torsion exponents
+    #[error("inconsistent parameters: key space does not match torsion size")]
+    InconsistentParameters,
+
+    /// The hash length does not match the message length
+    #[error("incorrect hash")]
+    IncorrectHash,
+
+    /// The authentication tag of a ciphertext does not match
+    #[error("ciphertext authentication failed")]
+    AuthenticationFailed,
+
+    /// A test vector does not match the recomputed values
+    #[error("test vector {0} does not match")]
+    TestVectorMismatch(usize),
+
+    /// The secret key is longer than the key space of the parameter set
+    #[error("invalid secret key length: {len} bytes, key space is {keyspace} bytes")]
+    InvalidSecretKeyLength {
+        /// Length of the secret key, in bytes
+        len: usize,
+        /// Size of the key space, in bytes
+        keyspace: usize,
+    },
+
+    /// No points were supplied to the isogeny computation
+    #[error("no points were supplied")]
+    MissingPoints,
+
+    /// A field element is encoded as a value that is not reduced modulo p
+    #[error("non-canonical field element encoding")]
+    NonCanonicalEncoding,
+
+    /// A round trip of `smoke_test` failed for a parameter set
+    #[error("smoke test failed for {set:?}")]
+    SmokeTestFailed {
+        /// Parameter set of the failing round trip
+        set: SikeParameterSet,
+        /// Error returned by the round trip, `None` if the keys did not match
+        #[source]
+        source: Option<Box<SikeError>>,
+    },
+
+    /// Point sampling ran out of candidates
+    #[error("point sampling exceeded its iteration limit")]
+    SamplingFailed,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    fn rng_error() -> getrandom::Error {
+        let code = std::num::NonZeroU32::new(getrandom::Error::CUSTOM_START).unwrap();
+        getrandom::Error::from(code)
+    }
+
+    fn failing_rng() -> Result<(), SikeError> {
+        Err(rng_error())?
+    }
+
+    #[test]
+    fn test_rng_error_source() {
+        let err = failing_rng().unwrap_err();
+
+        let source = err.source().unwrap();
+        let root = source.downcast_ref::<getrandom::Error>().unwrap();
+
+        assert_eq!(*root, rng_error());
+    }
+
+    #[test]
+    fn test_parse_error_source() {
+        use crate::ff::PrimeFieldP434;
+
+        let err = PrimeFieldP434::from_string("not hex").unwrap_err();
+
+        assert!(err
+            .source()
+            .unwrap()
+            .downcast_ref::<rug::integer::ParseIntegerError>()
+            .is_some());
+    }
+}
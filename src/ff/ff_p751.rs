@@ -4,8 +4,12 @@
 
 use crate::constants::cs_p751::SIKE_P751_P;
 
-use crate::ff::FiniteField;
+use crate::{
+    error::SikeError,
+    ff::{random_below, FiniteField},
+};
 use hex;
+use rand_core::RngCore;
 
 use once_cell::sync::Lazy;
 
@@ -24,10 +28,9 @@ pub struct PrimeFieldP751 {
 
 impl PrimeFieldP751 {
     /// Parse a string into and element of the finite field
-    pub fn from_string(s: &str) -> Result<Self, String> {
-        Integer::from_str_radix(&s, 16)
-            .or_else(|_| Err(String::from("Cannot parse from string")))
-            .and_then(|val| Ok(Self { val }))
+    pub fn from_string(s: &str) -> Result<Self, SikeError> {
+        let val = Integer::from_str_radix(&s, 16)?;
+        Ok(Self { val })
     }
 }
 
@@ -70,6 +73,20 @@ impl FiniteField for PrimeFieldP751 {
         }
     }
 
+    #[inline]
+    fn from_u64(n: u64) -> Self {
+        Self {
+            val: Integer::from(n) % Self::order(),
+        }
+    }
+
+    #[inline]
+    fn random_with_rng<R: RngCore>(rng: &mut R) -> Self {
+        Self {
+            val: random_below(rng, Self::order()),
+        }
+    }
+
     #[inline]
     fn neg(&self) -> Self {
         Self {
@@ -78,10 +95,10 @@ impl FiniteField for PrimeFieldP751 {
     }
 
     #[inline]
-    fn inv(&self) -> Result<Self, String> {
+    fn inv(&self) -> Result<Self, SikeError> {
         Integer::from(&self.val)
             .invert(Self::order())
-            .or_else(|_| Err(String::from("Cannot invert")))
+            .or_else(|_| Err(SikeError::NotInvertible))
             .and_then(|val| Ok(Self { val }))
     }
 
@@ -105,7 +122,7 @@ impl FiniteField for PrimeFieldP751 {
     }
 
     #[inline]
-    fn div(&self, other: &Self) -> Result<Self, String> {
+    fn div(&self, other: &Self) -> Result<Self, SikeError> {
         Ok(self.mul(&other.inv()?))
     }
 
@@ -114,15 +131,19 @@ impl FiniteField for PrimeFieldP751 {
         self.sub(&other).is_zero()
     }
 
-    fn into_bytes(self) -> Vec<u8> {
+    #[inline]
+    fn is_square(&self) -> bool {
+        self.val.legendre(Self::order()) >= 0
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
         self.val.to_digits::<u8>(MsfBe)
     }
 
-    fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+    fn from_bytes(bytes: &[u8]) -> Result<Self, SikeError> {
         let s = hex::encode(bytes);
 
-        Integer::from_str_radix(&s, 16)
-            .or_else(|_| Err(String::from("Cannot parse from bytes")))
-            .and_then(|val| Ok(Self { val }))
+        let val = Integer::from_str_radix(&s, 16)?;
+        Ok(Self { val })
     }
 }
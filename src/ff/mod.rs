@@ -3,6 +3,9 @@
 //! Provides the standard structure for finite fields and their quadratic extensions.
 //! It also includes specific finite fields implementation used for SIKE
 
+use crate::error::SikeError;
+use rand_core::RngCore;
+use rug::{integer::Order::MsfBe, Integer};
 use std::fmt::Debug;
 
 pub mod ff_p434;
@@ -29,11 +32,17 @@ pub trait FiniteField: Sized {
     /// Returns the multiplicative identity of the field
     fn one() -> Self;
 
+    /// Returns the element of the field corresponding to the integer `n`
+    fn from_u64(n: u64) -> Self;
+
+    /// Returns an element of the field drawn uniformly at random from `rng`
+    fn random_with_rng<R: RngCore>(rng: &mut R) -> Self;
+
     /// Returns the additive inverse of the element
     fn neg(&self) -> Self;
 
     /// Returns the multiplicative inverse of the element
-    fn inv(&self) -> Result<Self, String>;
+    fn inv(&self) -> Result<Self, SikeError>;
 
     /// Defines the addition of two elements
     fn add(&self, other: &Self) -> Self;
@@ -45,16 +54,101 @@ pub trait FiniteField: Sized {
     fn mul(&self, other: &Self) -> Self;
 
     /// Defines the divison of two elements
-    fn div(&self, other: &Self) -> Result<Self, String>;
+    fn div(&self, other: &Self) -> Result<Self, SikeError>;
 
     /// Checks if two elements are equal
     fn equals(&self, other: &Self) -> bool;
 
+    /// Checks if the element is a square in the field (zero included)
+    fn is_square(&self) -> bool;
+
     /// Converts the element to a bytes representation
-    fn into_bytes(self) -> Vec<u8>;
+    fn to_bytes(&self) -> Vec<u8>;
+
+    /// Converts the element to a bytes representation, see `to_bytes`
+    fn into_bytes(self) -> Vec<u8> {
+        self.to_bytes()
+    }
+
+    /// Converts the element to bytes where each coordinate over 𝔽ₚ is left-padded to
+    /// exactly `len` bytes, so that all the elements have the same length
+    fn to_bytes_fixed(&self, len: usize) -> Result<Vec<u8>, SikeError> {
+        let bytes = self.to_bytes();
+        if bytes.len() > len {
+            return Err(SikeError::InvalidEncoding("element too large"));
+        }
+
+        let mut fixed = vec![0; len - bytes.len()];
+        fixed.extend_from_slice(&bytes);
+        Ok(fixed)
+    }
+
+    /// Converts the element to bytes where each coordinate over 𝔽ₚ is written as exactly
+    /// `len` little-endian bytes, the encoding of the specification (ref `fp2_encode`)
+    fn to_bytes_le(&self, len: usize) -> Result<Vec<u8>, SikeError> {
+        let mut bytes = self.to_bytes_fixed(len)?;
+        bytes.reverse();
+        Ok(bytes)
+    }
 
     /// Converts a bytes representation to an element of the finite field
-    fn from_bytes(bytes: &[u8]) -> Result<Self, String>;
+    ///
+    /// Values are not checked against the order, see `from_bytes_strict`
+    fn from_bytes(bytes: &[u8]) -> Result<Self, SikeError>;
+
+    /// Converts a bytes representation to an element of the finite field, rejecting values
+    /// that are not reduced with `NonCanonicalEncoding`
+    ///
+    /// Each element then has a single encoding (up to leading zeros), so that a decoded
+    /// public key or ciphertext cannot be re-encoded differently.
+    fn from_bytes_strict(bytes: &[u8]) -> Result<Self, SikeError> {
+        let elem = Self::from_bytes(bytes)?;
+
+        // Operations reduce modulo the order, so a value >= p comes out of them changed
+        let minimal = &bytes[bytes.iter().take_while(|&&b| b == 0).count()..];
+        if elem.add(&Self::zero()).into_bytes() != minimal {
+            return Err(SikeError::NonCanonicalEncoding);
+        }
+        Ok(elem)
+    }
+}
+
+/// Inverts all the elements of `xs` with a single field inversion (Montgomery's trick)
+///
+/// Fails with `NotInvertible` if any element is zero.
+pub(crate) fn batch_inv<K: FiniteField + Clone>(xs: &[K]) -> Result<Vec<K>, SikeError> {
+    // prefix[i] = xs[0] * ... * xs[i - 1]
+    let mut prefix = Vec::with_capacity(xs.len());
+    let mut acc = K::one();
+    for x in xs {
+        prefix.push(acc.clone());
+        acc = acc.mul(x);
+    }
+
+    // inv = (xs[0] * ... * xs[i])^-1 at step i
+    let mut inv = acc.inv()?;
+    let mut invs = vec![K::zero(); xs.len()];
+    for (i, x) in xs.iter().enumerate().rev() {
+        invs[i] = inv.mul(&prefix[i]);
+        inv = inv.mul(x);
+    }
+
+    Ok(invs)
+}
+
+/// Extra random bytes drawn before reduction, bounding the bias by 2^-128
+const RANDOM_EXTRA_BYTES: usize = 16;
+
+/// Draws an integer uniformly at random in `[0, modulus)`
+///
+/// The sample is 128 bits wider than the modulus before reduction, so that
+/// the bias of the modular reduction is negligible.
+pub(crate) fn random_below<R: RngCore>(rng: &mut R, modulus: &Integer) -> Integer {
+    let len = (modulus.significant_bits() as usize + 7) / 8 + RANDOM_EXTRA_BYTES;
+    let mut bytes = vec![0; len];
+    rng.fill_bytes(&mut bytes);
+
+    Integer::from_digits(&bytes, MsfBe) % modulus
 }
 
 /// Given a specific finite field 𝔽ₚ, represents an element of
@@ -76,6 +170,107 @@ impl<F: FiniteField> QuadraticExtension<F> {
     pub fn from(a: F, b: F) -> Self {
         Self { a, b }
     }
+
+    /// Norm `a² + b²` of `z = a + i b`, i.e. `z * conj(z)`, as an element of the base field
+    pub fn norm(&self) -> F {
+        self.a.mul(&self.a).add(&self.b.mul(&self.b))
+    }
+
+    /// Converts the element to bytes, each half being prefixed by its length on 2 bytes (big endian)
+    ///
+    /// Unlike `into_bytes`, no padding is added.
+    pub fn into_bytes_framed(self) -> Result<Vec<u8>, SikeError> {
+        use crate::utils::conversion::concatenate;
+        use std::convert::TryFrom;
+
+        let part1 = self.a.into_bytes();
+        let part2 = self.b.into_bytes();
+
+        let len1 = u16::try_from(part1.len())
+            .map_err(|_| SikeError::InvalidEncoding("element too large"))?;
+        let len2 = u16::try_from(part2.len())
+            .map_err(|_| SikeError::InvalidEncoding("element too large"))?;
+
+        Ok(concatenate(&[
+            &len1.to_be_bytes(),
+            &part1,
+            &len2.to_be_bytes(),
+            &part2,
+        ]))
+    }
+
+    /// Element from a length-framed byte representation (see `into_bytes_framed`)
+    pub fn from_bytes_framed(bytes: &[u8]) -> Result<Self, SikeError> {
+        let (a, rest) = Self::read_frame(bytes)?;
+        let (b, rest) = Self::read_frame(rest)?;
+
+        if !rest.is_empty() {
+            return Err(SikeError::InvalidEncoding(
+                "trailing bytes after framed element",
+            ));
+        }
+
+        Ok(Self::from(F::from_bytes(a)?, F::from_bytes(b)?))
+    }
+
+    /// Converts the element to bytes where each half is left-padded to exactly `len` bytes
+    pub fn into_bytes_fixed(self, len: usize) -> Result<Vec<u8>, SikeError> {
+        Ok([self.a.to_bytes_fixed(len)?, self.b.to_bytes_fixed(len)?].concat())
+    }
+
+    /// Element from a representation produced by `into_bytes_fixed`, values that are not
+    /// reduced are rejected (see `FiniteField::from_bytes_strict`)
+    pub fn from_bytes_fixed(bytes: &[u8]) -> Result<Self, SikeError> {
+        if bytes.len() % 2 != 0 {
+            return Err(SikeError::InvalidEncoding("odd length fixed element"));
+        }
+
+        let (a, b) = bytes.split_at(bytes.len() / 2);
+        Ok(Self::from(
+            F::from_bytes_strict(a)?,
+            F::from_bytes_strict(b)?,
+        ))
+    }
+
+    /// Splits a length-prefixed frame from the start of `bytes`
+    fn read_frame(bytes: &[u8]) -> Result<(&[u8], &[u8]), SikeError> {
+        if bytes.len() < 2 {
+            return Err(SikeError::InvalidEncoding("missing frame length"));
+        }
+
+        let len = u16::from_be_bytes([bytes[0], bytes[1]]) as usize;
+        let bytes = &bytes[2..];
+
+        if bytes.len() < len {
+            return Err(SikeError::InvalidEncoding("truncated frame"));
+        }
+
+        Ok(bytes.split_at(len))
+    }
+}
+
+impl<F: FiniteField + Clone> QuadraticExtension<F> {
+    /// Frobenius conjugate `a - i b` of `z = a + i b`
+    pub fn conjugate(&self) -> Self {
+        Self {
+            a: self.a.clone(),
+            b: self.b.neg(),
+        }
+    }
+
+    /// True if `z = a + i b` lies in the base field 𝔽ₚ, i.e. if `b = 0`
+    pub fn is_base_field(&self) -> bool {
+        self.b.is_zero()
+    }
+
+    /// The element as an element of 𝔽ₚ, if it lies in the base field
+    pub fn base_field_part(&self) -> Option<F> {
+        if self.is_base_field() {
+            Some(self.a.clone())
+        } else {
+            None
+        }
+    }
 }
 
 impl<F: FiniteField + Debug> FiniteField for QuadraticExtension<F> {
@@ -101,6 +296,20 @@ impl<F: FiniteField + Debug> FiniteField for QuadraticExtension<F> {
         }
     }
 
+    fn from_u64(n: u64) -> Self {
+        Self {
+            a: F::from_u64(n),
+            b: F::zero(),
+        }
+    }
+
+    fn random_with_rng<R: RngCore>(rng: &mut R) -> Self {
+        Self {
+            a: F::random_with_rng(rng),
+            b: F::random_with_rng(rng),
+        }
+    }
+
     fn neg(&self) -> Self {
         Self {
             a: self.a.neg(),
@@ -119,7 +328,7 @@ impl<F: FiniteField + Debug> FiniteField for QuadraticExtension<F> {
         self.add(&other.neg())
     }
 
-    fn div(&self, other: &Self) -> Result<Self, String> {
+    fn div(&self, other: &Self) -> Result<Self, SikeError> {
         Ok(self.mul(&other.inv()?))
     }
 
@@ -136,10 +345,8 @@ impl<F: FiniteField + Debug> FiniteField for QuadraticExtension<F> {
         }
     }
 
-    fn inv(&self) -> Result<Self, String> {
-        let asq = self.a.mul(&self.a);
-        let bsq = self.b.mul(&self.b);
-        let inv_norm = asq.add(&bsq).inv()?;
+    fn inv(&self) -> Result<Self, SikeError> {
+        let inv_norm = self.norm().inv()?;
 
         Ok(Self {
             a: inv_norm.mul(&self.a),
@@ -151,11 +358,16 @@ impl<F: FiniteField + Debug> FiniteField for QuadraticExtension<F> {
         self.a.equals(&other.a) && self.b.equals(&other.b)
     }
 
-    fn into_bytes(self) -> Vec<u8> {
+    /// An element of 𝔽ₚ(i) is a square if and only if its norm `a² + b²` is a square in 𝔽ₚ
+    fn is_square(&self) -> bool {
+        self.norm().is_square()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
         use crate::utils::conversion::concatenate;
 
-        let part1 = self.a.into_bytes();
-        let part2 = self.b.into_bytes();
+        let part1 = self.a.to_bytes();
+        let part2 = self.b.to_bytes();
 
         // Left padding to the nearest power of 2
         let p21 = part1.len().next_power_of_two();
@@ -168,20 +380,73 @@ impl<F: FiniteField + Debug> FiniteField for QuadraticExtension<F> {
         concatenate(&[&pad1, &part1, &pad2, &part2])
     }
 
+    fn to_bytes_fixed(&self, len: usize) -> Result<Vec<u8>, SikeError> {
+        Ok([self.a.to_bytes_fixed(len)?, self.b.to_bytes_fixed(len)?].concat())
+    }
+
+    fn to_bytes_le(&self, len: usize) -> Result<Vec<u8>, SikeError> {
+        Ok([self.a.to_bytes_le(len)?, self.b.to_bytes_le(len)?].concat())
+    }
+
     /// Element from byte representation (ref `ostofp2` Algorithm 1.2.4.)
-    fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+    fn from_bytes(bytes: &[u8]) -> Result<Self, SikeError> {
         let n = bytes.len() / 2;
         let a = F::from_bytes(&bytes[..n])?;
         let b = F::from_bytes(&bytes[n..])?;
         Ok(Self::from(a, b))
     }
+
+    fn from_bytes_strict(bytes: &[u8]) -> Result<Self, SikeError> {
+        let n = bytes.len() / 2;
+        let a = F::from_bytes_strict(&bytes[..n])?;
+        let b = F::from_bytes_strict(&bytes[n..])?;
+        Ok(Self::from(a, b))
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    use crate::constants::cs_p434::{SIKE_P434_XP20, SIKE_P434_XP21};
+    use crate::constants::cs_p434::{SIKE_P434_P, SIKE_P434_XP20, SIKE_P434_XP21};
+
+    #[test]
+    fn test_from_bytes_strict() {
+        let p = Integer::from_str_radix(SIKE_P434_P, 16).unwrap();
+        let p_plus_one = Integer::from(&p + 1).to_digits::<u8>(rug::integer::Order::MsfBe);
+
+        // Lenient decoding keeps the value, which is p + 1 = 1 mod p
+        let lenient = PrimeFieldP434::from_bytes(&p_plus_one).unwrap();
+        assert!(lenient.equals(&PrimeFieldP434::one()));
+
+        assert!(matches!(
+            PrimeFieldP434::from_bytes_strict(&p_plus_one),
+            Err(SikeError::NonCanonicalEncoding)
+        ));
+        assert!(matches!(
+            PrimeFieldP434::from_bytes_strict(&p.to_digits::<u8>(rug::integer::Order::MsfBe)),
+            Err(SikeError::NonCanonicalEncoding)
+        ));
+
+        // Reduced values are accepted, in both halves of 𝔽ₚ(i)
+        let one = PrimeFieldP434::one().into_bytes();
+        assert!(PrimeFieldP434::from_bytes_strict(&one)
+            .unwrap()
+            .equals(&PrimeFieldP434::one()));
+
+        let mut padded_one = vec![0; p_plus_one.len() - 1];
+        padded_one.extend_from_slice(&one);
+        let bytes = [&p_plus_one[..], &padded_one].concat();
+        assert!(QuadraticExtension::<PrimeFieldP434>::from_bytes(&bytes).is_ok());
+        assert!(QuadraticExtension::<PrimeFieldP434>::from_bytes_strict(&bytes).is_err());
+        assert!(QuadraticExtension::<PrimeFieldP434>::from_bytes_fixed(&bytes).is_err());
+
+        // Public keys and ciphertexts are decoded strictly
+        let pk = crate::isogeny::PublicKey::<QuadraticExtension<PrimeFieldP434>>::from_bytes(
+            &bytes, &bytes, &bytes,
+        );
+        assert!(matches!(pk, Err(SikeError::NonCanonicalEncoding)));
+    }
 
     #[test]
     fn test_conversion_ff434_bytes() {
@@ -211,6 +476,67 @@ mod tests {
         assert!(q.equals(&q_recovered));
     }
 
+    #[test]
+    fn test_conversion_quadratic_bytes_fixed() {
+        type Fp2 = QuadraticExtension<PrimeFieldP434>;
+
+        // 1 and 256i have the same variable-size encoding once left-padded to 4 bytes
+        let x = Fp2::from(PrimeFieldP434::one(), PrimeFieldP434::zero());
+        let y = Fp2::from(PrimeFieldP434::zero(), PrimeFieldP434::from_u64(256));
+        assert_eq!(x.to_bytes(), [1, 0]);
+        assert_eq!(y.to_bytes(), [0, 0, 1, 0]);
+
+        let (x_fixed, y_fixed) = (x.to_bytes_fixed(55).unwrap(), y.to_bytes_fixed(55).unwrap());
+        assert_eq!(x_fixed.len(), 110);
+        assert_eq!(y_fixed.len(), 110);
+        assert_ne!(x_fixed, y_fixed);
+        assert!(Fp2::from_bytes_fixed(&y_fixed).unwrap().equals(&y));
+    }
+
+    #[test]
+    fn test_conversion_quadratic_bytes_le() {
+        type Fp2 = QuadraticExtension<PrimeFieldP434>;
+
+        // Real part first, each coordinate reversed on its own
+        let x = Fp2::from(
+            PrimeFieldP434::from_u64(0x0102),
+            PrimeFieldP434::from_u64(3),
+        );
+        let le = x.to_bytes_le(55).unwrap();
+        assert_eq!(le.len(), 110);
+        assert_eq!(le[..3], [2, 1, 0]);
+        assert_eq!(le[55..57], [3, 0]);
+
+        let mut fixed = x.to_bytes_fixed(55).unwrap();
+        fixed[..55].reverse();
+        fixed[55..].reverse();
+        assert_eq!(le, fixed);
+        assert!(x.to_bytes_le(1).is_err());
+    }
+
+    #[test]
+    fn test_conversion_quadratic_bytes_framed() {
+        let num1 = PrimeFieldP434::from_string(SIKE_P434_XP20).unwrap();
+        let num2 = PrimeFieldP434::from_string(SIKE_P434_XP21).unwrap();
+
+        let q = QuadraticExtension::from(num1.clone(), num2.clone());
+        let framed = q.clone().into_bytes_framed().unwrap();
+        let q_recovered = QuadraticExtension::from_bytes_framed(&framed).unwrap();
+
+        assert!(q.equals(&q_recovered));
+        assert!(framed.len() < q.into_bytes().len());
+
+        // A small component is not padded to the size of the other one
+        let small = QuadraticExtension::from(PrimeFieldP434::from_u64(3), num2);
+        let framed = small.clone().into_bytes_framed().unwrap();
+        let small_recovered = QuadraticExtension::from_bytes_framed(&framed).unwrap();
+
+        assert!(small.equals(&small_recovered));
+        assert!(framed.len() < small.into_bytes().len());
+
+        assert!(QuadraticExtension::<PrimeFieldP434>::from_bytes_framed(&framed[..10]).is_err());
+    }
+
     #[test]
     fn test_ff() {
         let one = PrimeFieldP434::one();
@@ -228,6 +554,154 @@ mod tests {
         println!("four2 = {:?}", four2);
     }
 
+    #[test]
+    fn test_from_u64() {
+        let one = PrimeFieldP434::one();
+        let mut six = PrimeFieldP434::zero();
+        for _ in 0..6 {
+            six = six.add(&one);
+        }
+
+        assert!(PrimeFieldP434::from_u64(6).equals(&six));
+        assert!(PrimeFieldP434::from_u64(0).equals(&PrimeFieldP434::zero()));
+
+        let q_six = QuadraticExtension::from(six, PrimeFieldP434::zero());
+        let q_zero: QuadraticExtension<PrimeFieldP434> = QuadraticExtension::zero();
+
+        assert!(QuadraticExtension::from_u64(6).equals(&q_six));
+        assert!(QuadraticExtension::<PrimeFieldP434>::from_u64(0).equals(&q_zero));
+    }
+
+    #[test]
+    fn test_random_below_distribution() {
+        let mut rng = rand_core::OsRng;
+        let modulus = Integer::from(13);
+        let samples_per_bucket = 1000;
+
+        let mut buckets = [0u32; 13];
+        for _ in 0..13 * samples_per_bucket {
+            let x = random_below(&mut rng, &modulus);
+            assert!(x >= 0 && x < modulus);
+            buckets[x.to_usize().unwrap()] += 1;
+        }
+
+        // Chi-square with 12 degrees of freedom, p ≈ 10⁻⁵
+        let expected = f64::from(samples_per_bucket);
+        let chi2: f64 = buckets
+            .iter()
+            .map(|&n| (f64::from(n) - expected).powi(2) / expected)
+            .sum();
+
+        assert!(chi2 < 45.0, "chi2 = {}", chi2);
+    }
+
+    #[test]
+    fn test_random_with_rng() {
+        let mut rng = rand_core::OsRng;
+
+        let x = PrimeFieldP434::random_with_rng(&mut rng);
+        let y = PrimeFieldP434::random_with_rng(&mut rng);
+        assert!(!x.equals(&y));
+
+        let z: QuadraticExtension<PrimeFieldP434> = QuadraticExtension::random_with_rng(&mut rng);
+        assert!(!z.a.equals(&z.b));
+    }
+
+    #[test]
+    fn test_is_square() {
+        let one = PrimeFieldP434::one();
+        let two = one.add(&one);
+        let four = two.mul(&two);
+
+        assert!(PrimeFieldP434::zero().is_square());
+        assert!(four.is_square());
+        // p = 3 mod 4, so -1 is not a square in 𝔽ₚ, but i² = -1 in 𝔽ₚ(i)
+        assert!(!one.neg().is_square());
+        assert!(!four.neg().is_square());
+
+        let minus_one: QuadraticExtension<PrimeFieldP434> = QuadraticExtension::one().neg();
+        assert!(minus_one.is_square());
+
+        let mut rng = rand_core::OsRng;
+        let x: QuadraticExtension<PrimeFieldP434> = QuadraticExtension::random_with_rng(&mut rng);
+        assert!(x.mul(&x).is_square());
+    }
+
+    #[test]
+    fn test_is_square_euler_criterion() {
+        type Fp2 = QuadraticExtension<PrimeFieldP434>;
+
+        // p - 1 is the representative of -1
+        let p_minus_one = Integer::from_digits(&PrimeFieldP434::one().neg().into_bytes(), MsfBe);
+        let p = Integer::from(&p_minus_one + 1);
+        let exponent = Integer::from(&p_minus_one >> 1);
+
+        // Euler's criterion: a is a non-zero square iff a^((p - 1) / 2) = 1
+        let euler = |x: &PrimeFieldP434| {
+            let val = Integer::from_digits(&x.clone().into_bytes(), MsfBe);
+            x.is_zero() || val.pow_mod(&exponent, &p).unwrap() == 1
+        };
+
+        assert!(PrimeFieldP434::one().is_square());
+        assert!(Fp2::one().is_square());
+
+        let mut rng = rand_core::OsRng;
+        for _ in 0..20 {
+            let x = PrimeFieldP434::random_with_rng(&mut rng);
+            assert_eq!(x.is_square(), euler(&x));
+            assert!(x.mul(&x).is_square());
+
+            let z = Fp2::random_with_rng(&mut rng);
+            assert_eq!(z.is_square(), euler(&z.norm()));
+            assert!(z.mul(&z).is_square());
+        }
+
+        // a + i, with a² + 1 a non-residue in 𝔽ₚ, is a non-residue in 𝔽ₚ(i)
+        let non_residue = (1..)
+            .map(|a| Fp2::from(PrimeFieldP434::from_u64(a), PrimeFieldP434::one()))
+            .find(|z| !euler(&z.norm()))
+            .unwrap();
+        assert!(!non_residue.is_square());
+    }
+
+    #[test]
+    fn test_batch_inv() {
+        type Fp2 = QuadraticExtension<PrimeFieldP434>;
+
+        let mut rng = rand_core::OsRng;
+        let xs: Vec<Fp2> = (0..5).map(|_| Fp2::random_with_rng(&mut rng)).collect();
+
+        let invs = batch_inv(&xs).unwrap();
+        for (x, x_inv) in xs.iter().zip(invs.iter()) {
+            assert!(x_inv.equals(&x.inv().unwrap()));
+        }
+
+        assert!(batch_inv::<Fp2>(&[]).unwrap().is_empty());
+
+        let with_zero = [Fp2::one(), Fp2::zero()];
+        assert!(matches!(
+            batch_inv(&with_zero),
+            Err(SikeError::NotInvertible)
+        ));
+    }
+
+    #[test]
+    fn test_conjugate_norm() {
+        type Fp2 = QuadraticExtension<PrimeFieldP434>;
+
+        let mut rng = rand_core::OsRng;
+        for _ in 0..10 {
+            let x = Fp2::random_with_rng(&mut rng);
+            let y = Fp2::random_with_rng(&mut rng);
+
+            let norm = Fp2::from(x.norm(), PrimeFieldP434::zero());
+            assert!(x.mul(&x.conjugate()).equals(&norm));
+
+            assert!(x.mul(&y).norm().equals(&x.norm().mul(&y.norm())));
+            assert!(x.conjugate().conjugate().equals(&x));
+        }
+    }
+
     #[test]
     fn test_qff() {
         let one = PrimeFieldP434::one();
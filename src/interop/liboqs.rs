@@ -0,0 +1,105 @@
+//! Key layouts of liboqs (SIKEp434)
+//!
+//! liboqs wraps the PQCrypto-SIDH reference, and serialises the KEM secret key as
+//! s ‖ sk3 ‖ pk, where s is the 16-byte rejection value, sk3 the 28-byte scalar in little
+//! endian, and pk the public key in the layout of `interop::pqcrypto`.
+//!
+//! Only the keys are converted. The KEM of this crate hashes its own encodings, so it does
+//! not decapsulate liboqs ciphertexts to the liboqs shared secret.
+
+use crate::{
+    error::SikeError,
+    ff::{PrimeFieldP434, QuadraticExtension},
+    interop::pqcrypto::{public_key_from_pqcrypto_bytes, to_pqcrypto_bytes, P434_PUBLIC_KEY_BYTES},
+    isogeny::{PublicKey, SecretKey},
+};
+
+/// Size in bytes of the rejection value s
+const P434_MESSAGE_BYTES: usize = 16;
+
+/// Size in bytes of the scalar sk3
+const P434_SCALAR_BYTES: usize = 28;
+
+/// Size in bytes of a SIKEp434 secret key in the liboqs layout
+pub const P434_SECRET_KEY_BYTES: usize =
+    P434_MESSAGE_BYTES + P434_SCALAR_BYTES + P434_PUBLIC_KEY_BYTES;
+
+type P434PublicKey = PublicKey<QuadraticExtension<PrimeFieldP434>>;
+
+/// Parses a public key given in the liboqs layout
+pub fn public_key_from_bytes(bytes: &[u8]) -> Result<P434PublicKey, SikeError> {
+    public_key_from_pqcrypto_bytes(bytes)
+}
+
+/// Parses a secret key given in the liboqs layout, into the `(s, sk3, pk3)` of `KEM::keygen`
+pub fn secret_key_from_bytes(
+    bytes: &[u8],
+) -> Result<(Vec<u8>, SecretKey, P434PublicKey), SikeError> {
+    if bytes.len() != P434_SECRET_KEY_BYTES {
+        return Err(SikeError::InvalidEncoding("secret key size mismatch"));
+    }
+
+    let (s, rest) = bytes.split_at(P434_MESSAGE_BYTES);
+    let (sk3, pk3) = rest.split_at(P434_SCALAR_BYTES);
+
+    // Both libraries read the scalar in little endian
+    Ok((
+        s.to_vec(),
+        SecretKey::from_bytes(sk3),
+        public_key_from_bytes(pk3)?,
+    ))
+}
+
+/// Converts the `(s, sk3, pk3)` of `KEM::keygen` to a secret key in the liboqs layout
+pub fn secret_key_to_bytes(
+    s: &[u8],
+    sk3: &SecretKey,
+    pk3: &P434PublicKey,
+) -> Result<Vec<u8>, SikeError> {
+    let mut scalar = sk3.to_bytes();
+    if s.len() != P434_MESSAGE_BYTES || scalar.len() > P434_SCALAR_BYTES {
+        return Err(SikeError::InvalidEncoding("secret key size mismatch"));
+    }
+    scalar.resize(P434_SCALAR_BYTES, 0);
+
+    Ok([s, &scalar, &to_pqcrypto_bytes(pk3)?].concat())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{interop::kat::P434_KAT0_SK, isogeny::sike_p434_params, utils::strategy::*, KEM};
+
+    #[test]
+    fn test_liboqs_secret_key_roundtrip() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+        let kem = KEM::setup(params).unwrap();
+
+        let (s, sk3, pk3) = kem.keygen().unwrap();
+        let sk_bytes = secret_key_to_bytes(&s, &sk3, &pk3).unwrap();
+        assert_eq!(sk_bytes.len(), P434_SECRET_KEY_BYTES);
+
+        let (s_imported, sk_imported, pk_imported) = secret_key_from_bytes(&sk_bytes).unwrap();
+
+        assert_eq!(s_imported, s);
+        assert_eq!(sk_imported.to_bytes()[..], sk_bytes[16..44]);
+        assert_eq!(pk_imported, pk3);
+        assert!(secret_key_from_bytes(&sk_bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn test_liboqs_known_answer_layout() {
+        let sk_bytes = hex::decode(P434_KAT0_SK).unwrap();
+
+        let (s, sk3, pk3) = secret_key_from_bytes(&sk_bytes).unwrap();
+
+        assert_eq!(s[..], sk_bytes[..16]);
+        assert_eq!(sk3.to_bytes()[..], sk_bytes[16..44]);
+        assert_eq!(pk3, public_key_from_bytes(&sk_bytes[44..]).unwrap());
+        assert_eq!(secret_key_to_bytes(&s, &sk3, &pk3).unwrap(), sk_bytes);
+    }
+}
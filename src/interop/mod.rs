@@ -0,0 +1,10 @@
+//! Interoperability with other SIKE implementations
+//!
+//! Only the uncompressed layouts are supported. The `SIKEp434_compressed` formats of the
+//! NIST submission need key compression (torsion basis generation, pairings and discrete
+//! logarithms), which the crate does not implement.
+
+#[cfg(test)]
+pub(crate) mod kat;
+pub mod liboqs;
+pub mod pqcrypto;
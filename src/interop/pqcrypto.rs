@@ -0,0 +1,113 @@
+//! Public key layout of the PQCrypto-SIDH reference implementation (SIKEp434)
+//!
+//! The reference encodes the public key as x(P) ‖ x(Q) ‖ x(R), each element of 𝔽ₚ²
+//! being written as its real part followed by its imaginary part, and each of those
+//! as 55 little-endian bytes. Only the uncompressed layout is supported.
+
+use crate::{
+    error::SikeError,
+    ff::{PrimeFieldP434, QuadraticExtension},
+    isogeny::{FixedPublicKeyP434, PublicKey},
+};
+use std::convert::TryInto;
+
+/// Size in bytes of a SIKEp434 public key in the reference layout
+pub const P434_PUBLIC_KEY_BYTES: usize = 330;
+
+/// Size in bytes of one coordinate of 𝔽ₚ
+const P434_COORD_BYTES: usize = P434_PUBLIC_KEY_BYTES / 6;
+
+/// Reverse the byte order of every coordinate, switching between big and little endian
+fn swap_coords_endianness(bytes: &mut [u8]) {
+    for coord in bytes.chunks_mut(P434_COORD_BYTES) {
+        coord.reverse();
+    }
+}
+
+/// Converts a public key to the PQCrypto-SIDH byte layout
+pub fn to_pqcrypto_bytes(
+    pk: &PublicKey<QuadraticExtension<PrimeFieldP434>>,
+) -> Result<[u8; P434_PUBLIC_KEY_BYTES], SikeError> {
+    let mut bytes = FixedPublicKeyP434::from_public_key(pk.clone())?.to_array();
+    swap_coords_endianness(&mut bytes);
+
+    Ok(bytes)
+}
+
+/// Parses a public key given in the PQCrypto-SIDH byte layout
+pub fn public_key_from_pqcrypto_bytes(
+    bytes: &[u8],
+) -> Result<PublicKey<QuadraticExtension<PrimeFieldP434>>, SikeError> {
+    let mut bytes: [u8; P434_PUBLIC_KEY_BYTES] = bytes
+        .try_into()
+        .map_err(|_| SikeError::InvalidEncoding("public key size mismatch"))?;
+    swap_coords_endianness(&mut bytes);
+
+    FixedPublicKeyP434::from_array(bytes).to_public_key()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::cs_p434::*,
+        interop::kat::P434_KAT0_PK,
+        isogeny::{sike_p434_params, CurveIsogenies, SecretKey},
+        utils::conversion::str_to_u64,
+    };
+
+    /// Little-endian encoding of a hexadecimal constant on 55 bytes
+    fn le_coord(s: &str) -> Vec<u8> {
+        let mut bytes = hex::decode(s).unwrap();
+        bytes.reverse();
+        bytes.resize(P434_COORD_BYTES, 0);
+        bytes
+    }
+
+    #[test]
+    fn test_pqcrypto_layout() {
+        let params = sike_p434_params(None, None).unwrap();
+        let pk = PublicKey {
+            x1: params.xp2,
+            x2: params.xq2,
+            x3: params.xr2,
+        };
+
+        let expected: Vec<u8> = [
+            SIKE_P434_XP20,
+            SIKE_P434_XP21,
+            SIKE_P434_XQ20,
+            SIKE_P434_XQ21,
+            SIKE_P434_XR20,
+            SIKE_P434_XR21,
+        ]
+        .iter()
+        .flat_map(|s| le_coord(s))
+        .collect();
+
+        assert_eq!(to_pqcrypto_bytes(&pk).unwrap().to_vec(), expected);
+        assert_eq!(public_key_from_pqcrypto_bytes(&expected).unwrap(), pk);
+    }
+
+    #[test]
+    fn test_pqcrypto_roundtrip() {
+        let nks3 = str_to_u64(SIKE_P434_NKS3).unwrap();
+        let sk = SecretKey::get_random_secret_key(nks3 as usize).unwrap();
+        let iso = CurveIsogenies::init(sike_p434_params(None, None).unwrap());
+        let pk = iso.isogen3_untyped(&sk).unwrap();
+
+        let bytes = to_pqcrypto_bytes(&pk).unwrap();
+        let recovered = public_key_from_pqcrypto_bytes(&bytes).unwrap();
+
+        assert_eq!(pk, recovered);
+        assert!(public_key_from_pqcrypto_bytes(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn test_pqcrypto_known_answer_layout() {
+        let bytes = hex::decode(P434_KAT0_PK).unwrap();
+        let pk = public_key_from_pqcrypto_bytes(&bytes).unwrap();
+
+        assert_eq!(to_pqcrypto_bytes(&pk).unwrap().to_vec(), bytes);
+    }
+}
@@ -0,0 +1,244 @@
+//! Reference key generation in affine coordinates, to cross-check the projective code
+//!
+//! Points are represented by their affine x-coordinate and curves by their coefficient A,
+//! on y² = x³ + Ax² + x. Every step divides, so this is much slower than `CurveIsogenies`,
+//! and is only meant for validation. Doubling, tripling, the ladder and 3-isogenies use the
+//! affine formulas of Costello and Hisil ("A simple and compact algorithm for SIDH with
+//! arbitrary degree isogenies"), and 2-isogenies those of Renes ("Computing isogenies between
+//! Montgomery curves using the action of (0, 0)"), independently from the projective formulas
+//! of the specification. 4-isogenies are the specification formulas with Z = 1, which give the
+//! same curve model as `CurveIsogenies`; a chain of 2-isogenies reaches an isomorphic curve
+//! but not the same public key.
+//!
+//! Degenerate inputs (a point at infinity or of order 2 where it is not expected) fail with
+//! `SikeError::NotInvertible`.
+
+use crate::{
+    error::SikeError,
+    ff::FiniteField,
+    isogeny::{PublicKey, PublicParameters, SecretKey},
+};
+
+/// x-coordinates of the three points carried along a walk
+type ThreeX<K> = (K, K, K);
+
+/// Computing the public key on the 2-torsion, as `CurveIsogenies::isogen2`
+pub fn isogen2<K: FiniteField + Clone>(
+    params: &PublicParameters<K>,
+    sk: &SecretKey,
+) -> Result<PublicKey<K>, SikeError> {
+    sk.check_length(params.keyspace2)?;
+
+    let a = K::from_u64(6);
+    let s = ladder(sk, &params.xp2, &params.xq2, &params.xr2, &a)?;
+    let points = (params.xp3.clone(), params.xq3.clone(), params.xr3.clone());
+
+    let (x1, x2, x3) = walk2(s, points, a, params.e2)?;
+    Ok(PublicKey::from_torsion_points(x1, x2, x3))
+}
+
+/// Computing the public key on the 3-torsion, as `CurveIsogenies::isogen3`
+pub fn isogen3<K: FiniteField + Clone>(
+    params: &PublicParameters<K>,
+    sk: &SecretKey,
+) -> Result<PublicKey<K>, SikeError> {
+    sk.check_length(params.keyspace3)?;
+
+    let a = K::from_u64(6);
+    let s = ladder(sk, &params.xp3, &params.xq3, &params.xr3, &a)?;
+    let points = (params.xp2.clone(), params.xq2.clone(), params.xr2.clone());
+
+    let (x1, x2, x3) = walk3(s, points, a, params.e3)?;
+    Ok(PublicKey::from_torsion_points(x1, x2, x3))
+}
+
+/// x(P + [m]Q) from x(P), x(Q) and x(Q - P), with the bits of `sk` as m
+fn ladder<K: FiniteField + Clone>(
+    sk: &SecretKey,
+    x_p: &K,
+    x_q: &K,
+    x_qmp: &K,
+    a: &K,
+) -> Result<K, SikeError> {
+    let (mut x0, mut x1, mut x2) = (x_q.clone(), x_p.clone(), x_qmp.clone());
+
+    // Invariants: x1 = x(P + [m mod 2^i]Q), x0 = x([2^i]Q), x2 = x(x0 - x1)
+    let mut bits = sk.bits_lsb_first().peekable();
+    while let Some(bit) = bits.next() {
+        if bit {
+            x1 = add(&x0, &x1, &x2)?;
+        } else {
+            x2 = add(&x0, &x2, &x1)?;
+        }
+
+        // [2^len]Q can be the point at infinity, which has no affine coordinate
+        if bits.peek().is_some() {
+            x0 = double(&x0, a)?;
+        }
+    }
+
+    Ok(x1)
+}
+
+/// x([2]P) = (x² - 1)² / 4x(x² + Ax + 1)
+fn double<K: FiniteField>(x: &K, a: &K) -> Result<K, SikeError> {
+    let one = K::one();
+    let four = K::from_u64(4);
+    let x2 = x.mul(x);
+
+    let num = x2.sub(&one);
+    let den = four.mul(x).mul(&x2.add(&a.mul(x)).add(&one));
+
+    num.mul(&num).div(&den)
+}
+
+/// x(P + Q) = (x_P x_Q - 1)² / x(P - Q) (x_P - x_Q)²
+fn add<K: FiniteField>(x_p: &K, x_q: &K, x_pmq: &K) -> Result<K, SikeError> {
+    let num = x_p.mul(x_q).sub(&K::one());
+    let diff = x_p.sub(x_q);
+
+    num.mul(&num).div(&x_pmq.mul(&diff.mul(&diff)))
+}
+
+/// x([3]P), as [2]P + P
+fn triple<K: FiniteField>(x: &K, a: &K) -> Result<K, SikeError> {
+    add(&double(x, a)?, x, x)
+}
+
+/// Walk the isogeny of kernel <S>, S of order 2^e: a 2-isogeny first when e is odd, then
+/// 4-isogenies, as `CurveIsogenies::isogen2`
+fn walk2<K: FiniteField + Clone>(
+    mut s: K,
+    mut points: ThreeX<K>,
+    mut a: K,
+    mut e: u64,
+) -> Result<ThreeX<K>, SikeError> {
+    if e % 2 == 1 {
+        let t = repeat(&s, e - 1, |x| double(x, &a))?;
+        if e > 1 {
+            s = two_isogeny_eval(&t, &s)?;
+        }
+        points = map(&points, |x| two_isogeny_eval(&t, x))?;
+        a = two_isogenous_curve(&t);
+        e -= 1;
+    }
+
+    for i in (0..e / 2).rev() {
+        // Kernel point of order 4, the last one is S itself
+        let t = repeat(&s, 2 * i, |x| double(x, &a))?;
+        if i > 0 {
+            s = four_isogeny_eval(&t, &s)?;
+        }
+        points = map(&points, |x| four_isogeny_eval(&t, x))?;
+        a = four_isogenous_curve(&t);
+    }
+
+    Ok(points)
+}
+
+/// Walk the isogeny of kernel <S>, S of order 3^e, one 3-isogeny at a time
+fn walk3<K: FiniteField + Clone>(
+    mut s: K,
+    mut points: ThreeX<K>,
+    mut a: K,
+    e: u64,
+) -> Result<ThreeX<K>, SikeError> {
+    for i in (0..e).rev() {
+        // Kernel point of order 3, the last one is S itself
+        let t = repeat(&s, i, |x| triple(x, &a))?;
+        if i > 0 {
+            s = three_isogeny_eval(&t, &s)?;
+        }
+        points = map(&points, |x| three_isogeny_eval(&t, x))?;
+        a = three_isogenous_curve(&t, &a);
+    }
+
+    Ok(points)
+}
+
+/// Apply `f` `n` times to `x`
+fn repeat<K: Clone>(x: &K, n: u64, f: impl Fn(&K) -> Result<K, SikeError>) -> Result<K, SikeError> {
+    (0..n).try_fold(x.clone(), |x, _| f(&x))
+}
+
+/// Apply `f` to each of the three points
+fn map<K>(
+    points: &ThreeX<K>,
+    f: impl Fn(&K) -> Result<K, SikeError>,
+) -> Result<ThreeX<K>, SikeError> {
+    Ok((f(&points.0)?, f(&points.1)?, f(&points.2)?))
+}
+
+/// Renes: kernel (α, 0) with α ≠ 0, A' = 2(1 - 2α²)
+fn two_isogenous_curve<K: FiniteField>(alpha: &K) -> K {
+    let one = K::one();
+    let two = one.add(&one);
+
+    two.mul(&one.sub(&two.mul(&alpha.mul(alpha))))
+}
+
+/// Renes: φ(x) = x(xα - 1) / (x - α)
+fn two_isogeny_eval<K: FiniteField>(alpha: &K, x: &K) -> Result<K, SikeError> {
+    x.mul(&x.mul(alpha).sub(&K::one())).div(&x.sub(alpha))
+}
+
+/// Kernel <(δ, ·)> of order 4, A' = 4δ⁴ - 2 (`4_iso_curve` with Z = 1)
+fn four_isogenous_curve<K: FiniteField>(delta: &K) -> K {
+    let d2 = delta.mul(delta);
+
+    K::from_u64(4).mul(&d2.mul(&d2)).sub(&K::from_u64(2))
+}
+
+/// φ(x) = (xδ - 1)² ((xδ - 1)² + x² - 1) / (x - δ)² ((x - δ)² - x² + 1) (`4_iso_eval` with
+/// Z = 1)
+fn four_isogeny_eval<K: FiniteField>(delta: &K, x: &K) -> Result<K, SikeError> {
+    let one = K::one();
+    let u = x.mul(delta).sub(&one);
+    let v = x.sub(delta);
+    let w = x.mul(x).sub(&one);
+
+    let (u2, v2) = (u.mul(&u), v.mul(&v));
+    u2.mul(&u2.add(&w)).div(&v2.mul(&v2.sub(&w)))
+}
+
+/// Costello-Hisil: kernel <(β, ·)> of order 3, A' = β(Aβ - 6β² + 6)
+fn three_isogenous_curve<K: FiniteField>(beta: &K, a: &K) -> K {
+    let six = K::from_u64(6);
+
+    a.mul(beta)
+        .sub(&six.mul(&beta.mul(beta)))
+        .add(&six)
+        .mul(beta)
+}
+
+/// Costello-Hisil: φ(x) = x (xβ - 1)² / (x - β)²
+fn three_isogeny_eval<K: FiniteField>(beta: &K, x: &K) -> Result<K, SikeError> {
+    let num = x.mul(beta).sub(&K::one());
+    let den = x.sub(beta);
+
+    x.mul(&num.mul(&num)).div(&den.mul(&den))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::isogeny::{sike_p434_params, CurveIsogenies};
+
+    #[test]
+    fn test_affine_reference_p434() {
+        let params = sike_p434_params(None, None).unwrap();
+        let iso = CurveIsogenies::init(params.clone());
+
+        let sk2 = SecretKey::get_random_secret_key(params.keyspace2 as usize).unwrap();
+        let sk3 = SecretKey::get_random_secret_key(params.keyspace3 as usize).unwrap();
+
+        assert_eq!(
+            isogen2(&params, &sk2).unwrap(),
+            iso.isogen2_untyped(&sk2).unwrap()
+        );
+        assert_eq!(
+            isogen3(&params, &sk3).unwrap(),
+            iso.isogen3_untyped(&sk3).unwrap()
+        );
+    }
+}
@@ -1,6 +1,19 @@
 //! Montgomery curve
 
-use crate::{ff::FiniteField, isogeny::PublicKey};
+use crate::{
+    error::SikeError,
+    ff::FiniteField,
+    isogeny::{point::Point, CurveIsogenies, PublicKey},
+    utils::{conversion, shake::ShakeRng},
+};
+
+use std::fmt::Debug;
+
+/// Domain separation tag used when hashing a message to a point
+const HASH_TO_POINT_TAG: &[u8] = b"rust-sike hash to point";
+
+/// Default number of candidates drawn by `Curve::hash_to_point`
+pub const HASH_TO_POINT_MAX_ITERATIONS: u32 = 256;
 
 /// Montgomery M_{A,1} Curve defined by (A : C) in projective cooridnates
 pub struct Curve<K> {
@@ -29,12 +42,7 @@ impl<K: FiniteField + Clone> Curve<K> {
     /// Curve with equation y² = x³ + 6x² + x (ref 1.3.2)
     #[inline]
     pub fn starting_curve() -> Curve<K> {
-        let one = K::one();
-        let two = one.add(&one);
-        let three = two.add(&one);
-        let six = three.add(&three);
-
-        Curve::from_coeffs(six, one)
+        Curve::from_coeffs(K::from_u64(6), K::one())
     }
 
     /// Convert a curve (A : C) to (A' : C') ~ (A + 2C : 4C)
@@ -62,9 +70,51 @@ impl<K: FiniteField + Clone> Curve<K> {
         Curve::from_coeffs(a, c)
     }
 
+    /// Ladder constant (A + 2C) / 4C, consumed by the doublings of the Montgomery ladder
+    ///
+    /// `double` and `triple` take it unnormalised, as the curve (A + 2C : 4C) of
+    /// `curve_plus`.
+    #[inline]
+    pub fn a24_plus(&self) -> Result<K, SikeError> {
+        let plus = self.curve_plus();
+        plus.a.div(&plus.c)
+    }
+
+    /// Ladder constant (A - 2C) / 4C
+    ///
+    /// `triple` takes the pair of constants as the curve (A + 2C : A - 2C) of
+    /// `curve_plus_minus`.
+    #[inline]
+    pub fn a24_minus(&self) -> Result<K, SikeError> {
+        let one = K::one();
+        let two = one.add(&one);
+        let four = two.add(&two);
+
+        self.a.sub(&two.mul(&self.c)).div(&four.mul(&self.c))
+    }
+
+    /// Byte encoding of the affine coefficient A / C, in the encoding of the public key points
+    ///
+    /// Projectively equal curves (A : C) ~ (λA : λC) have the same encoding, so this
+    /// identifies the curve (not its isomorphism class, see `j_invariant`). Fails if C = 0.
+    pub fn a_coefficient_bytes(&self) -> Result<Vec<u8>, SikeError> {
+        Ok(self.a.div(&self.c)?.into_bytes())
+    }
+
+    /// Quadratic twist M_{-A,1} of the curve M_{A,1}, as (-A : C)
+    ///
+    /// The map (x, y) ↦ (-x, y) sends M_{A,1} to the curve -y² = x³ - Ax² + x, i.e. the twist
+    /// of M_{-A,1} by -1. Both curves share the j-invariant, which only depends on A². Over 𝔽ₚ
+    /// (p = 3 mod 4) -1 is not a square and this is the non-trivial quadratic twist; over
+    /// 𝔽ₚ(i), i² = -1 and (x, y) ↦ (-x, iy) is an isomorphism with the original curve.
+    #[inline]
+    pub fn twist(&self) -> Curve<K> {
+        Curve::from_coeffs(self.a.neg(), self.c.clone())
+    }
+
     /// Montgomery j-invariant (ref Algorithm 9 p.56)
     #[inline]
-    pub fn j_invariant(&self) -> Result<K, String> {
+    pub fn j_invariant(&self) -> Result<K, SikeError> {
         let j = self.a.mul(&self.a); // 1.
         let t1 = self.c.mul(&self.c); //2.
         let t0 = t1.add(&t1); // 3.
@@ -85,15 +135,58 @@ impl<K: FiniteField + Clone> Curve<K> {
         t0.div(&j) // 15.
     }
 
+    /// Checks if `x` is the x-coordinate of a point of the curve, i.e. if
+    /// x³ + (A/C)x² + x is a square
+    #[inline]
+    pub fn is_x_on_curve(&self, x: &K) -> bool {
+        // C (C x³ + A x² + C x) is a square iff (x³ + (A/C) x² + x) is one
+        let x2 = x.mul(x);
+        let rhs = self.c.mul(&x2.mul(x).add(x)).add(&self.a.mul(&x2));
+
+        rhs.mul(&self.c).is_square()
+    }
+
+    /// Deterministically maps a message to a point of the curve (try-and-increment)
+    ///
+    /// Candidates x = H(tag ‖ counter ‖ msg) are drawn until `is_x_on_curve` holds, at most
+    /// `HASH_TO_POINT_MAX_ITERATIONS` times (see `hash_to_point_with_limit`).
+    /// **This is not constant-time:** the number of attempts depends on `msg`.
+    pub fn hash_to_point(&self, msg: &[u8]) -> Result<Point<K>, SikeError> {
+        self.hash_to_point_with_limit(msg, HASH_TO_POINT_MAX_ITERATIONS)
+    }
+
+    /// Maps a message to a point of the curve as `hash_to_point`, drawing at most
+    /// `max_iterations` candidates
+    ///
+    /// On a curve, about half of the candidates are x-coordinates of points, so running out
+    /// of candidates points to a wrong curve (or a wrong square test) rather than bad luck.
+    /// Fails with `SamplingFailed` in that case.
+    pub fn hash_to_point_with_limit(
+        &self,
+        msg: &[u8],
+        max_iterations: u32,
+    ) -> Result<Point<K>, SikeError> {
+        for counter in 0..max_iterations {
+            let seed = conversion::concatenate(&[HASH_TO_POINT_TAG, &counter.to_be_bytes(), msg]);
+            let x = K::random_with_rng(&mut ShakeRng::new(&seed));
+
+            if self.is_x_on_curve(&x) {
+                return Ok(Point::from_x(x));
+            }
+        }
+
+        Err(SikeError::SamplingFailed)
+    }
+
     /// Generates a curve from three elements of 𝔽ₚ(i), or returns None
     /// (ref `cfpk` Algorithm 1.2.1 )
     #[inline]
-    pub fn from_public_key(pk: &PublicKey<K>) -> Result<Curve<K>, String> {
+    pub fn from_public_key(pk: &PublicKey<K>) -> Result<Curve<K>, SikeError> {
         let (x_p, x_q, x_r) = (&pk.x1, &pk.x2, &pk.x3);
 
         // 1.
         if x_p.is_zero() || x_q.is_zero() || x_r.is_zero() {
-            return Err(String::from("Incorrect public key!"));
+            return Err(SikeError::InvalidPublicKey);
         }
 
         // 2.
@@ -107,6 +200,12 @@ impl<K: FiniteField + Clone> Curve<K> {
             .sub(&x_q.mul(&x_r));
         let num = num.mul(&num);
         let denom = four.mul(&x_p).mul(&x_q).mul(&x_r);
+
+        // Non-reduced coordinates can vanish mod p despite passing the check above
+        if denom.is_zero() {
+            return Err(SikeError::InvalidPublicKey);
+        }
+
         let frac = num.div(&denom)?;
         let a = frac.sub(&x_p).sub(&x_q).sub(&x_r);
         let c = one;
@@ -115,3 +214,19 @@ impl<K: FiniteField + Clone> Curve<K> {
         Ok(Curve::from_coeffs(a, c))
     }
 }
+
+impl<K: FiniteField + Clone + Debug> Curve<K> {
+    /// 2-isogeny of kernel <(x, ·)>, for a point of order 2 on this curve with x ≠ 0
+    ///
+    /// Returns the image curve, as (A' + 2C' : 4C') like the walks on the 2-torsion, and the
+    /// evaluation of the isogeny at points of this curve. The formulas only depend on the
+    /// kernel (ref `2_iso_curve` and `2_iso_eval`, Algorithms 11 and 12 p.57).
+    pub fn two_isogeny_from_kernel_x(&self, x: &K) -> (Curve<K>, impl Fn(&Point<K>) -> Point<K>) {
+        let kernel = Point::from_x(x.clone());
+        let image = CurveIsogenies::two_isogenous_curve(&kernel);
+
+        (image, move |q: &Point<K>| {
+            CurveIsogenies::two_isogeny_eval(&kernel, q)
+        })
+    }
+}
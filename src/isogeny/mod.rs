@@ -1,31 +1,103 @@
 //! Tools for isogeny computations
 
-use bitvec::prelude::*;
-use std::{collections::VecDeque, convert::TryInto, fmt::Debug};
+use std::{
+    collections::VecDeque,
+    convert::TryFrom,
+    fmt::Debug,
+    time::{Duration, Instant},
+};
 
+#[cfg(feature = "reference")]
+pub mod affine_reference;
 mod curve;
 mod point;
 mod publickey;
 mod publicparams;
 mod secretkey;
+mod walk;
 
-use crate::{ff::FiniteField, isogeny::point::Point};
+use crate::{error::SikeError, ff::FiniteField, isogeny::point::Point, utils::strategy};
 
 pub use crate::isogeny::{
-    curve::Curve, publickey::PublicKey, publicparams::*, secretkey::SecretKey,
+    curve::Curve,
+    publickey::{
+        FixedPublicKey, FixedPublicKeyP434, FixedPublicKeyP503, FixedPublicKeyP610,
+        FixedPublicKeyP751, PreparedPublicKey, ProjectivePublicKey, PublicKey, PublicKey2,
+        PublicKey3,
+    },
+    publicparams::*,
+    secretkey::SecretKey,
+    walk::{ThreeIsogenyWalk, TwoIsogenyWalk},
 };
 
 type ThreePoints<K> = (Point<K>, Point<K>, Point<K>);
 
+/// Curve constants precomputed once for repeated doublings
+struct DoublingContext<K> {
+    /// Curve in (A + 2C : 4C) form
+    curve: Curve<K>,
+
+    /// Normalised constant (A + 2C) / 4C, if 4C is invertible
+    a_24: Option<K>,
+}
+
+impl<K: FiniteField + Clone> DoublingContext<K> {
+    /// Precompute the doubling constants for a curve given as (A + 2C : 4C)
+    fn new(curve: &Curve<K>) -> Self {
+        Self {
+            curve: curve.clone(),
+            a_24: curve.a.div(&curve.c).ok(),
+        }
+    }
+}
+
+/// Timings of `CurveIsogenies::benchmark_walk`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WalkBenchReport {
+    /// Number of `isogen2` calls timed for each walk
+    pub iterations: usize,
+
+    /// Total time of the simple walk
+    pub simple: Duration,
+
+    /// Total time of the walk following the tree-traversal strategy
+    pub optimized: Duration,
+}
+
+impl WalkBenchReport {
+    /// Ratio of the simple time over the optimized time, above 1 if the strategy pays off
+    pub fn speedup(&self) -> f64 {
+        self.simple.as_secs_f64() / self.optimized.as_secs_f64()
+    }
+}
+
 /// SIKE structure for computing isogenies
 pub struct CurveIsogenies<K> {
     params: PublicParameters<K>,
+
+    /// Starting curve in (A : C) form
+    start_curve: Curve<K>,
+
+    /// Starting curve in (A + 2C : 4C) form
+    start_curve_plus: Curve<K>,
+
+    /// Starting curve in (A + 2C : A - 2C) form
+    start_curve_pm: Curve<K>,
 }
 
 impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
     /// Initialise the SIKE structure with given parameters
     pub fn init(params: PublicParameters<K>) -> Self {
-        Self { params }
+        let start_curve = Curve::starting_curve();
+        let start_curve_plus = start_curve.curve_plus();
+        let start_curve_pm = start_curve.curve_plus_minus();
+
+        Self {
+            params,
+            start_curve,
+            start_curve_plus,
+            start_curve_pm,
+        }
     }
 
     /// Coordinate doubling (ref. `xDBL`, Algorithm 3 p. 54)
@@ -49,16 +121,52 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
         Point { x, z }
     }
 
+    /// Coordinate doubling with precomputed curve constants (ref `xDBL` Algorithm 3 p. 54)
+    ///  * Input: P, doubling context. Output: [2]P
+    ///
+    /// With a normalised context (C = 1) the multiplication by C (step 5) is skipped
+    #[inline]
+    fn double_with_ctx(p: &Point<K>, ctx: &DoublingContext<K>) -> Point<K> {
+        let a_24 = match &ctx.a_24 {
+            Some(a_24) => a_24,
+            None => return Self::double(p, &ctx.curve),
+        };
+
+        let t0 = p.x.sub(&p.z); // 1.
+        let t1 = p.x.add(&p.z); // 2.
+        let t0 = t0.mul(&t0); // 3.
+        let t1 = t1.mul(&t1); // 4.
+        let x = t0.mul(&t1); // 6.
+        let t1 = t1.sub(&t0); // 7.
+        let t2 = a_24.mul(&t1); // 8.
+        let z = t0.add(&t2); // 9.
+        let z = z.mul(&t1); // 10.
+
+        Point { x, z }
+    }
+
     /// Repeated coordinate doubling (ref `xDBLe` Algorithm 4 p.55)
     /// Input: P, e. Output : [2^e]P
     #[inline]
-    fn ndouble(p: Point<K>, e: u64, curve: &Curve<K>) -> Point<K> {
+    fn ndouble(p: &Point<K>, e: u64, curve: &Curve<K>) -> Point<K> {
+        if e == 0 {
+            return p.clone();
+        }
+        Self::ndouble_with_ctx(p, e, &DoublingContext::new(curve))
+    }
+
+    /// Repeated coordinate doubling with precomputed curve constants
+    /// Input: P, e, doubling context. Output : [2^e]P
+    ///
+    /// P is borrowed, so that the walks can keep it without cloning it first.
+    #[inline]
+    fn ndouble_with_ctx(p: &Point<K>, e: u64, ctx: &DoublingContext<K>) -> Point<K> {
         if e == 0 {
-            return p;
+            return p.clone();
         }
-        let mut point = p;
-        for _ in 1..=e {
-            point = Self::double(&point, curve);
+        let mut point = Self::double_with_ctx(p, ctx);
+        for _ in 1..e {
+            point = Self::double_with_ctx(&point, ctx);
         }
         point
     }
@@ -137,42 +245,40 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
     }
 
     /// Repeated point tripling (ref `xTPLe` Algorithm 7 p.56)
-    ///  * Input: P, e. Output: [E^e]P
+    ///  * Input: P, e. Output: [3^e]P
     #[inline]
-    fn ntriple(p: Point<K>, e: u64, curve: &Curve<K>) -> Point<K> {
+    fn ntriple(p: &Point<K>, e: u64, curve: &Curve<K>) -> Point<K> {
         if e == 0 {
-            return p;
+            return p.clone();
         }
-        let mut point = p;
-        for _ in 1..=e {
+        let mut point = Self::triple(p, curve);
+        for _ in 1..e {
             point = Self::triple(&point, curve);
         }
         point
     }
 
     /// Three point ladder (ref `Ladder3pt` Algorithm 8 p.56)
-    ///  * Input: m (binary), x_p, x_q, x_(Q-P)
+    ///  * Input: bits of m, low weight bits first (e.g. `SecretKey::bits_lsb_first`), x_p,
+    ///    x_q, x_(Q-P)
     ///  * Output: P + [m]Q
+    ///
+    /// Not constant-time: the swap of the points branches on the bits of `m`.
     #[inline]
     fn three_pts_ladder(
-        m: &BitSlice<Msb0, u8>,
+        m: impl Iterator<Item = bool>,
         x_p: K,
         x_q: K,
         x_qmp: K,
         curve: &Curve<K>,
-    ) -> Result<Point<K>, String> {
+    ) -> Result<Point<K>, SikeError> {
         let mut p0 = Point::from_x(x_q);
         let mut p1 = Point::from_x(x_p);
         let mut p2 = Point::from_x(x_qmp);
 
-        let one = K::one();
-        let two = one.add(&one);
-        let four = two.add(&two);
-
-        let a_24_plus = &curve.a.add(&two).div(&four)?;
+        let a_24_plus = &curve.a24_plus()?;
 
-        // Start with low weight bits
-        for &m_i in m.iter().rev() {
+        for m_i in m {
             if m_i {
                 let (p0v, p1v) = Self::double_and_add(&p0, &p1, &p2, a_24_plus);
                 p0 = p0v;
@@ -190,7 +296,7 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
     /// Recovering Montgomery curve coefficient (ref `get_A`, Algorithm 10 p. 57)
     ///  * Input: x_p, x_q, x_(Q-P)
     ///  * Output: A
-    fn _from_points(x_p: K, x_q: K, x_qmp: K) -> Result<Curve<K>, String> {
+    fn _from_points(x_p: K, x_q: K, x_qmp: K) -> Result<Curve<K>, SikeError> {
         let t1 = x_p.add(&x_q); //1.
         let t0 = x_p.mul(&x_q); //2.
         let a = x_qmp.mul(&t1); //3.
@@ -262,6 +368,9 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
     /// Evaluate the four-isogeny at a point (ref `4_iso_eval` Algorithm 14 p. 58)
     ///  * Input: (k1, k2, k3), Q
     ///  * Output: Q' on a 4-isogenous curve
+    ///
+    /// The point at infinity (z = 0) and the points of the kernel are mapped to infinity.
+    /// Normalising such a point with `x.div(&z)` fails with `SikeError::NotInvertible`.
     #[inline]
     pub fn four_isogeny_eval(k1: &K, k2: &K, k3: &K, q: &Point<K>) -> Point<K> {
         let t0 = q.x.add(&q.z); // 1.
@@ -319,6 +428,8 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
     /// Evaluate the three-isogeny at a point (ref `3_iso_eval` Algorithm 16 p.58)
     ///  * Input: k1, k2, Q
     ///  * Output: Q' on the 3-isogenous curve
+    ///
+    /// The point at infinity (z = 0) and the points of the kernel are mapped to infinity.
     #[inline]
     pub fn three_isogeny_eval(q: &Point<K>, k1: &K, k2: &K) -> Point<K> {
         let t0 = q.x.add(&q.z); // 1.
@@ -344,57 +455,28 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
     fn two_e_iso(
         &self,
         s: Point<K>,
-        mut opt: Option<ThreePoints<K>>,
+        opt: Option<ThreePoints<K>>,
         curve: &Curve<K>,
     ) -> (Curve<K>, Option<ThreePoints<K>>) {
-        let mut c = curve.clone();
-        let mut s = s;
-        let mut e2 = self.params.e2;
-
-        if e2 % 2 == 1 {
-            e2 -= 1;
-            let t = Self::ndouble(s.clone(), e2, &c);
-
-            // 3.
-            c = Self::two_isogenous_curve(&t);
-
-            // 4.
-            s = Self::two_isogeny_eval(&t, &s);
-
-            // 5 and 6.
-            opt = opt.map(|(p1, p2, p3)| {
-                (
-                    Self::two_isogeny_eval(&t, &p1),
-                    Self::two_isogeny_eval(&t, &p2),
-                    Self::two_isogeny_eval(&t, &p3),
-                )
-            });
-        }
-
-        // 1.
-        for e in (0..=e2 - 2).rev().step_by(2) {
-            // 2.
-            let t = Self::ndouble(s.clone(), e, &c);
-
-            // 3.
-            let (new_c, k1, k2, k3) = Self::four_isogenous_curve(&t);
-            c = new_c;
-
-            // 4.
-            s = Self::four_isogeny_eval(&k1, &k2, &k3, &s);
+        Self::two_iso_degree(s, self.params.e2, opt, curve)
+    }
 
-            // 5 and 6.
-            opt = opt.map(|(p1, p2, p3)| {
-                (
-                    Self::four_isogeny_eval(&k1, &k2, &k3, &p1),
-                    Self::four_isogeny_eval(&k1, &k2, &k3, &p2),
-                    Self::four_isogeny_eval(&k1, &k2, &k3, &p3),
-                )
-            });
-        }
+    /// Computing and evaluating a 2^k isogeny for any k (generalises `2_e_iso` Algorithm 17 p.59)
+    ///  * Input: S of order 2^k, k, curve as (A + 2C : 4C)
+    ///  * Optional input: three points on the curve
+    ///  * Output: E/<S> as (A + 2C : 4C)
+    ///  * Optional output: three points on the new curve
+    #[inline]
+    pub fn two_iso_degree(
+        kernel: Point<K>,
+        k: u64,
+        opt: Option<ThreePoints<K>>,
+        curve: &Curve<K>,
+    ) -> (Curve<K>, Option<ThreePoints<K>>) {
+        let mut walk = TwoIsogenyWalk::with_points(kernel, k, opt, curve);
+        while walk.advance() {}
 
-        // 7.
-        (c, opt)
+        walk.into_parts()
     }
 
     /// Computing & evaluating 2^e-isogeny, optimised version (ref `2_e_iso` Algorithm 19 p. 60)
@@ -406,21 +488,33 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
     fn two_e_iso_optim(
         &self,
         s: Point<K>,
-        mut opt: Option<ThreePoints<K>>,
+        opt: Option<ThreePoints<K>>,
         curve_plus: &Curve<K>,
         strategy: &[usize],
-    ) -> Result<(Curve<K>, Option<ThreePoints<K>>), String> {
-        if self.params.e2 as usize / 2 - 1 != strategy.len() {
-            return Err(String::from("Invalid strategy"));
-        }
+    ) -> Result<(Curve<K>, Option<ThreePoints<K>>), SikeError> {
+        strategy::validate_shape(strategy, self.params.e2 as usize / 2)?;
+        self.two_e_iso_optim_unchecked(s, opt, curve_plus, strategy)
+    }
 
+    /// `two_e_iso_optim` without the upfront check of the shape of `strategy`: an invalid
+    /// strategy is only detected during the walk, after part of the work
+    fn two_e_iso_optim_unchecked(
+        &self,
+        s: Point<K>,
+        mut opt: Option<ThreePoints<K>>,
+        curve_plus: &Curve<K>,
+        strategy: &[usize],
+    ) -> Result<(Curve<K>, Option<ThreePoints<K>>), SikeError> {
         let mut curve = curve_plus.clone();
         let mut s = s;
         let mut e2 = self.params.e2;
 
+        // For odd e2, one 2-isogeny is peeled off so that the 4-isogeny tree below has
+        // depth e2 / 2. `isogen2` and `isoex2` both pass the curve as (A + 2C : 4C), which
+        // is the form `ndouble` works in.
         if e2 % 2 == 1 {
             e2 -= 1;
-            let t = Self::ndouble(s.clone(), e2, &curve);
+            let t = Self::ndouble(&s, e2, &curve);
 
             // 3.
             curve = Self::two_isogenous_curve(&t);
@@ -438,6 +532,9 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
             })
         }
 
+        // Doubling constants, computed on demand for the current curve
+        let mut ctx = None;
+
         // 1.
         let mut queue = VecDeque::new();
 
@@ -449,11 +546,9 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
 
         // 4.
         while !queue.is_empty() {
-            let s_i = if i <= strategy.len() {
-                // Valid conversion on 32 ad 64 bits arch, cannot panic
-                strategy[i - 1].try_into().unwrap()
-            } else {
-                1
+            let s_i = match strategy.get(i - 1) {
+                Some(&s_i) => u64::try_from(s_i).map_err(|_| SikeError::InvalidStrategy)?,
+                None => 1,
             };
 
             // 5.
@@ -465,6 +560,7 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
                 // 7.
                 let (new_curve, k1, k2, k3) = Self::four_isogenous_curve(&p);
                 curve = new_curve;
+                ctx = None;
 
                 // 8.
                 let mut tmp_queue = VecDeque::new();
@@ -493,12 +589,13 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
                         Self::four_isogeny_eval(&k1, &k2, &k3, &p3),
                     )
                 })
-            } else if h > s_i {
-                // 17.
-                queue.push_back((h, p.clone()));
+            } else if s_i > 0 && h > s_i {
+                // 18. (before 17., so that p is moved into the queue rather than cloned)
+                let ctx = ctx.get_or_insert_with(|| DoublingContext::new(&curve));
+                let p_prime = Self::ndouble_with_ctx(&p, 2 * s_i, ctx);
 
-                // 18.
-                let p_prime = Self::ndouble(p, 2 * s_i, &curve);
+                // 17.
+                queue.push_back((h, p));
 
                 // 19.
                 queue.push_back((h - s_i, p_prime));
@@ -507,7 +604,7 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
                 i += 1;
             } else {
                 // 22.
-                return Err(String::from("Invalid strategy !"));
+                return Err(SikeError::InvalidStrategy);
             }
         }
 
@@ -524,34 +621,13 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
     fn three_e_iso(
         &self,
         s: Point<K>,
-        mut opt: Option<ThreePoints<K>>,
+        opt: Option<ThreePoints<K>>,
         curve: &Curve<K>,
     ) -> (Curve<K>, Option<ThreePoints<K>>) {
-        let mut c = curve.clone();
-        let mut s = s;
-
-        for e in (0..self.params.e3).rev() {
-            // 2.
-            let t = Self::ntriple(s.clone(), e, &c);
+        let mut walk = ThreeIsogenyWalk::with_points(s, self.params.e3, opt, curve);
+        while walk.advance() {}
 
-            // 3.
-            let (new_c, k1, k2) = Self::three_isogenous_curve(&t);
-            c = new_c;
-
-            // 4.
-            s = Self::three_isogeny_eval(&s, &k1, &k2);
-
-            // 5 and 6.
-            opt = opt.map(|(p1, p2, p3)| {
-                (
-                    Self::three_isogeny_eval(&p1, &k1, &k2),
-                    Self::three_isogeny_eval(&p2, &k1, &k2),
-                    Self::three_isogeny_eval(&p3, &k1, &k2),
-                )
-            })
-        }
-
-        (c, opt)
+        walk.into_parts()
     }
 
     /// Computing & evaluating 3^e-isogeny, optimised version (ref `3_e_iso` Algorithm 20 p. 61)
@@ -563,14 +639,23 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
     fn three_e_iso_optim(
         &self,
         s: Point<K>,
-        mut opt: Option<ThreePoints<K>>,
+        opt: Option<ThreePoints<K>>,
         curve_pm: &Curve<K>,
         strategy: &[usize],
-    ) -> Result<(Curve<K>, Option<ThreePoints<K>>), String> {
-        if self.params.e3 as usize - 1 != strategy.len() {
-            return Err(String::from("Invalid strategy"));
-        }
+    ) -> Result<(Curve<K>, Option<ThreePoints<K>>), SikeError> {
+        strategy::validate_shape(strategy, self.params.e3 as usize)?;
+        self.three_e_iso_optim_unchecked(s, opt, curve_pm, strategy)
+    }
 
+    /// `three_e_iso_optim` without the upfront check of the shape of `strategy`: an invalid
+    /// strategy is only detected during the walk, after part of the work
+    fn three_e_iso_optim_unchecked(
+        &self,
+        s: Point<K>,
+        mut opt: Option<ThreePoints<K>>,
+        curve_pm: &Curve<K>,
+        strategy: &[usize],
+    ) -> Result<(Curve<K>, Option<ThreePoints<K>>), SikeError> {
         let mut curve = curve_pm.clone();
 
         // 1.
@@ -584,11 +669,9 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
 
         // 4.
         while !queue.is_empty() {
-            let s_i = if i <= strategy.len() {
-                // Valid conversion on 32 ad 64 bits arch, cannot panic
-                strategy[i - 1].try_into().unwrap()
-            } else {
-                1
+            let s_i = match strategy.get(i - 1) {
+                Some(&s_i) => u64::try_from(s_i).map_err(|_| SikeError::InvalidStrategy)?,
+                None => 1,
             };
 
             // 5.
@@ -628,12 +711,12 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
                         Self::three_isogeny_eval(&p3, &k1, &k2),
                     )
                 })
-            } else if h > s_i {
-                // 17.
-                queue.push_back((h, p.clone()));
+            } else if s_i > 0 && h > s_i {
+                // 18. (before 17., so that p is moved into the queue rather than cloned)
+                let p_prime = Self::ntriple(&p, s_i, &curve);
 
-                // 18.
-                let p_prime = Self::ntriple(p, s_i, &curve);
+                // 17.
+                queue.push_back((h, p));
 
                 // 19.
                 queue.push_back((h - s_i, p_prime));
@@ -642,7 +725,7 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
                 i += 1;
             } else {
                 // 22.
-                return Err(String::from("Invalid strategy !"));
+                return Err(SikeError::InvalidStrategy);
             }
         }
 
@@ -650,15 +733,54 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
         Ok((curve, opt))
     }
 
+    /// `isogen2`, without the tag of the torsion side
+    #[inline]
+    pub(crate) fn isogen2_untyped(&self, sk: &SecretKey) -> Result<PublicKey<K>, SikeError> {
+        let (_, pk) = self.isogen2_with_curve(sk)?;
+        Ok(pk)
+    }
+
     /// Computing public key on the 2-torsion (ref `isogen_2` Algo 21 p.62)
     ///  * Input: secret key, [tree traversal strategy]
-    ///  * Output: public key
+    ///  * Output: public key, tagged as coming from the 2-torsion side
+    ///
+    pub fn isogen2(&self, sk: &SecretKey) -> Result<PublicKey2<K>, SikeError> {
+        self.isogen2_untyped(sk).map(PublicKey2::new)
+    }
+
+    /// Computing public key and image curve on the 2-torsion (ref `isogen_2` Algo 21 p.62)
+    ///  * Input: secret key, [tree traversal strategy]
+    ///  * Output: image curve E/<S> as (A : C), public key
+    #[inline]
+    fn isogen2_with_curve(&self, sk: &SecretKey) -> Result<(Curve<K>, PublicKey<K>), SikeError> {
+        let (curve, pk) = self.isogen2_projective_with_curve(sk)?;
+
+        // 5, 6.
+        Ok((curve, pk.to_affine()?))
+    }
+
+    /// Computing public key on the 2-torsion, without normalising the points
+    ///  * Input: secret key
+    ///  * Output: public key in projective coordinates
     ///
+    /// `ProjectivePublicKey::to_affine` recovers the output of `isogen2`.
+    #[inline]
+    pub fn isogen2_projective(&self, sk: &SecretKey) -> Result<ProjectivePublicKey<K>, SikeError> {
+        let (_, pk) = self.isogen2_projective_with_curve(sk)?;
+        Ok(pk)
+    }
+
+    /// Steps 1 to 4 of `isogen2`, returning the image curve as (A : C)
     #[inline]
-    pub fn isogen2(&self, sk: &SecretKey) -> Result<PublicKey<K>, String> {
+    fn isogen2_projective_with_curve(
+        &self,
+        sk: &SecretKey,
+    ) -> Result<(Curve<K>, ProjectivePublicKey<K>), SikeError> {
+        sk.check_length(self.params.keyspace2)?;
+
         // 1.
-        let curve = Curve::starting_curve();
-        let curve_plus = curve.curve_plus();
+        let curve = &self.start_curve;
+        let curve_plus = &self.start_curve_plus;
 
         // 2.
         let xp3 = self.params.xp3.clone();
@@ -672,37 +794,129 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
         let xp2 = self.params.xp2.clone();
         let xq2 = self.params.xq2.clone();
         let xr2 = self.params.xr2.clone();
-        let s = Self::three_pts_ladder(&sk.to_bits(), xp2, xq2, xr2, &curve)?;
+        let s = Self::three_pts_ladder(sk.bits_lsb_first(), xp2, xq2, xr2, curve)?;
 
         // 4.
         let opt = Some((p1, p2, p3));
 
-        let (_, opt) = match &self.params.e2_strategy {
-            Some(strat) => self.two_e_iso_optim(s, opt, &curve_plus, &strat)?,
-            None => self.two_e_iso(s, opt, &curve_plus),
+        let (curve_plus, opt) = match &self.params.e2_strategy {
+            Some(strat) => self.two_e_iso_optim(s, opt, curve_plus, &strat)?,
+            None => self.two_e_iso(s, opt, curve_plus),
         };
 
-        // 5.
         let (p1, p2, p3) = match opt {
             Some(p) => p,
-            None => return Err(String::from("No points where supplied")),
+            None => return Err(SikeError::MissingPoints),
         };
-        let x1 = p1.x.div(&p1.z)?;
-        let x2 = p2.x.div(&p2.z)?;
-        let x3 = p3.x.div(&p3.z)?;
 
-        // 6.
-        Ok(PublicKey { x1, x2, x3 })
+        let pk = ProjectivePublicKey {
+            p1: (p1.x, p1.z),
+            p2: (p2.x, p2.z),
+            p3: (p3.x, p3.z),
+        };
+        Ok((Self::curve_from_plus(curve_plus), pk))
     }
 
-    /// Computing public key on the 3-torsion (ref `isogen_3` Algorithm 22 p.62)
+    /// Computing the image curve on the 2-torsion, without evaluating the basis points
     ///  * Input: secret key
-    ///  * Output: public key
+    ///  * Output: image curve E/<S> as (A : C)
+    ///
+    /// This skips the evaluation and normalisation of the three points of `isogen2`, and
+    /// is meant for validation only: the output cannot be transmitted as a public key.
+    pub fn isogen2_curve_only(&self, sk: &SecretKey) -> Result<Curve<K>, SikeError> {
+        sk.check_length(self.params.keyspace2)?;
+
+        let xp2 = self.params.xp2.clone();
+        let xq2 = self.params.xq2.clone();
+        let xr2 = self.params.xr2.clone();
+        let s = Self::three_pts_ladder(sk.bits_lsb_first(), xp2, xq2, xr2, &self.start_curve)?;
+
+        let (curve_plus, _) = match &self.params.e2_strategy {
+            Some(strat) => self.two_e_iso_optim(s, None, &self.start_curve_plus, strat)?,
+            None => self.two_e_iso(s, None, &self.start_curve_plus),
+        };
+
+        Ok(Self::curve_from_plus(curve_plus))
+    }
+
+    /// Back from (A + 2C : 4C) to (A : C)
+    #[inline]
+    fn curve_from_plus(curve_plus: Curve<K>) -> Curve<K> {
+        let two = K::one().add(&K::one());
+        let four = two.add(&two);
+
+        Curve::from_coeffs(
+            curve_plus.a.mul(&four).sub(&curve_plus.c.mul(&two)),
+            curve_plus.c,
+        )
+    }
+
+    /// Verifying a public key on the 2-torsion against its secret key
+    ///  * Input: secret key, claimed public key
+    ///  * Output: true if recomputing the isogeny from the secret key yields the
+    ///    same public key, and the image curve has the same j-invariant as the
+    ///    curve recovered from the public key
+    pub fn verify_isogen2(&self, sk: &SecretKey, pk: &PublicKey<K>) -> bool {
+        let (image_curve, expected_pk) = match self.isogen2_with_curve(sk) {
+            Ok(res) => res,
+            Err(_) => return false,
+        };
+
+        if expected_pk != *pk {
+            return false;
+        }
+
+        let j_image = image_curve.j_invariant();
+        let j_pk = Curve::from_public_key(pk).and_then(|curve| curve.j_invariant());
+
+        match (j_image, j_pk) {
+            (Ok(j_image), Ok(j_pk)) => j_image.equals(&j_pk),
+            _ => false,
+        }
+    }
+
+    /// Times `iterations` calls to `isogen2` with the simple walk and with the 2-torsion
+    /// strategy of the parameters
+    ///
+    /// This is a quick way to decide whether to supply a strategy on given hardware, not a
+    /// substitute for the criterion benchmarks: there is no warm-up nor statistical analysis.
+    /// Fails with `InvalidStrategy` if the parameters have no 2-torsion strategy.
+    pub fn benchmark_walk(
+        &self,
+        sk: &SecretKey,
+        iterations: usize,
+    ) -> Result<WalkBenchReport, SikeError> {
+        if self.params.e2_strategy.is_none() {
+            return Err(SikeError::InvalidStrategy);
+        }
+
+        let mut simple_params = self.params.clone();
+        simple_params.e2_strategy = None;
+        let simple = Self::init(simple_params);
+
+        let time = |iso: &Self| -> Result<Duration, SikeError> {
+            let start = Instant::now();
+            for _ in 0..iterations {
+                iso.isogen2_untyped(sk)?;
+            }
+            Ok(start.elapsed())
+        };
+
+        Ok(WalkBenchReport {
+            iterations,
+            simple: time(&simple)?,
+            optimized: time(self)?,
+        })
+    }
+
+    /// `isogen3`, without the tag of the torsion side
     #[inline]
-    pub fn isogen3(&self, sk: &SecretKey) -> Result<PublicKey<K>, String> {
+    pub(crate) fn isogen3_untyped(&self, sk: &SecretKey) -> Result<PublicKey<K>, SikeError> {
+        sk.check_length(self.params.keyspace3)?;
+
         // 1.
-        let curve = Curve::starting_curve();
-        let curve_pm = curve.curve_plus_minus();
+        let curve = &self.start_curve;
+        let curve_pm = &self.start_curve_pm;
 
         // 2.
         let xp2 = self.params.xp2.clone();
@@ -719,34 +933,61 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
         let xq3 = self.params.xq3.clone();
         let xr3 = self.params.xr3.clone();
 
-        let s = Self::three_pts_ladder(&sk.to_bits(), xp3, xq3, xr3, &curve)?;
+        let s = Self::three_pts_ladder(sk.bits_lsb_first(), xp3, xq3, xr3, curve)?;
 
         // 4.
         let opt = Some((p1, p2, p3));
 
         let (_, opt) = match &self.params.e3_strategy {
-            Some(strat) => self.three_e_iso_optim(s, opt, &curve_pm, &strat)?,
-            None => self.three_e_iso(s, opt, &curve_pm),
+            Some(strat) => self.three_e_iso_optim(s, opt, curve_pm, &strat)?,
+            None => self.three_e_iso(s, opt, curve_pm),
         };
 
         // 5.
         let (p1, p2, p3) = match opt {
             Some(p) => p,
-            None => return Err(String::from("No points where supplied")),
+            None => return Err(SikeError::MissingPoints),
+        };
+        let pk = ProjectivePublicKey {
+            p1: (p1.x, p1.z),
+            p2: (p2.x, p2.z),
+            p3: (p3.x, p3.z),
         };
-        let x1 = p1.x.div(&p1.z)?;
-        let x2 = p2.x.div(&p2.z)?;
-        let x3 = p3.x.div(&p3.z)?;
 
         // 6.
-        Ok(PublicKey { x1, x2, x3 })
+        pk.to_affine()
     }
 
-    /// Establishing shared keys on the 2-torsion, (ref `isoex_2` Algorithm 23 p.63)
-    ///  * Input: secret key, public key, [tree traversal strategy]
-    ///  * Output: j-invariant
+    /// Computing public key on the 3-torsion (ref `isogen_3` Algorithm 22 p.62)
+    ///  * Input: secret key
+    ///  * Output: public key, tagged as coming from the 3-torsion side
+    pub fn isogen3(&self, sk: &SecretKey) -> Result<PublicKey3<K>, SikeError> {
+        self.isogen3_untyped(sk).map(PublicKey3::new)
+    }
+
+    /// Computing the public key on the 3-torsion of the secret key `sk + delta` (mod 3^e3)
+    ///  * Input: secret key, offset
+    ///  * Output: public key
+    ///
+    /// **Research-grade:** this evolves a keypair for experiments, it is not part of SIKE and
+    /// its security has not been analysed.
+    pub fn extend_key(&self, sk: &SecretKey, delta: &SecretKey) -> Result<PublicKey<K>, SikeError> {
+        // 3^e3, the cofactor of the 2-torsion
+        let order = self.params.cofactor_2()?;
+        self.isogen3_untyped(&sk.add_mod(delta, &order))
+    }
+
+    /// Computing public keys on the 3-torsion for a batch of secret keys
+    ///  * Input: secret keys
+    ///  * Output: public keys, in the same order
+    #[cfg(not(feature = "rayon"))]
+    pub fn isogen3_batch(&self, sks: &[SecretKey]) -> Vec<Result<PublicKey<K>, SikeError>> {
+        sks.iter().map(|sk| self.isogen3_untyped(sk)).collect()
+    }
+
+    /// `isoex2`, on a public key without the tag of the torsion side
     #[inline]
-    pub fn isoex2(&self, sk: &SecretKey, pk: &PublicKey<K>) -> Result<K, String> {
+    pub(crate) fn isoex2_untyped(&self, sk: &SecretKey, pk: &PublicKey<K>) -> Result<K, SikeError> {
         let one = K::one();
         let two = one.add(&one);
         let four = two.add(&two);
@@ -754,17 +995,50 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
         // 1.
         let curve = Curve::from_public_key(pk)?;
 
+        // 3.
+        let curve_plus = Curve::from_coeffs(curve.a.add(&two), four);
+
+        self.isoex2_with_curves(sk, pk, &curve, &curve_plus)
+    }
+
+    /// Establishing shared keys on the 2-torsion with a prepared public key, as `isoex2`
+    /// without the recovery of the curve
+    pub fn isoex2_prepared(
+        &self,
+        sk: &SecretKey,
+        pk: &PreparedPublicKey<K>,
+    ) -> Result<K, SikeError> {
+        self.isoex2_with_curves(sk, &pk.pk, &pk.curve, &pk.curve_plus)
+    }
+
+    /// Steps 2, 4 to 7 of `isoex2`, from the curve of `pk` and its form (A + 2C : 4C)
+    fn isoex2_with_curves(
+        &self,
+        sk: &SecretKey,
+        pk: &PublicKey<K>,
+        curve: &Curve<K>,
+        curve_plus: &Curve<K>,
+    ) -> Result<K, SikeError> {
+        sk.check_length(self.params.keyspace2)?;
+
+        let one = K::one();
+        let two = one.add(&one);
+        let four = two.add(&two);
+
         // 2.
         let (x1, x2, x3) = (&pk.x1, &pk.x2, &pk.x3);
-        let s = Self::three_pts_ladder(&sk.to_bits(), x1.clone(), x2.clone(), x3.clone(), &curve)?;
-
-        // 3.
-        let curve_plus = Curve::from_coeffs(curve.a.add(&two), four.clone());
+        let s = Self::three_pts_ladder(
+            sk.bits_lsb_first(),
+            x1.clone(),
+            x2.clone(),
+            x3.clone(),
+            curve,
+        )?;
 
         // 4.
         let (curve_plus, _) = match &self.params.e2_strategy {
-            Some(strat) => self.two_e_iso_optim(s, None, &curve_plus, &strat)?,
-            None => self.two_e_iso(s, None, &curve_plus),
+            Some(strat) => self.two_e_iso_optim(s, None, curve_plus, &strat)?,
+            None => self.two_e_iso(s, None, curve_plus),
         };
 
         // 5.
@@ -777,28 +1051,72 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
         Ok(curve.j_invariant()?)
     }
 
-    /// Establishing shared keys on the 3-torsion (ref `isoex_3` Algorithm 24 p.63)
-    ///  * Input: secret key, public key, [tree traversal strategy]
-    ///  * Output: a j-invariant
+    /// `isoex3`, on a public key without the tag of the torsion side
     #[inline]
-    pub fn isoex3(&self, sk: &SecretKey, pk: &PublicKey<K>) -> Result<K, String> {
+    pub(crate) fn isoex3_untyped(&self, sk: &SecretKey, pk: &PublicKey<K>) -> Result<K, SikeError> {
         let one = K::one();
         let two = one.add(&one);
 
         // 1.
         let curve = Curve::from_public_key(pk)?;
 
-        // 2.
-        let (x1, x2, x3) = (&pk.x1, &pk.x2, &pk.x3);
-        let s = Self::three_pts_ladder(&sk.to_bits(), x1.clone(), x2.clone(), x3.clone(), &curve)?;
-
         // 3.
         let curve_pm = Curve::from_coeffs(curve.a.add(&two), curve.a.sub(&two));
 
+        self.isoex3_with_curves(sk, pk, &curve, &curve_pm)
+    }
+
+    /// Establishing shared keys on the 2-torsion, (ref `isoex_2` Algorithm 23 p.63)
+    ///  * Input: secret key, public key of the 3-torsion side, [tree traversal strategy]
+    ///  * Output: j-invariant
+    pub fn isoex2(&self, sk: &SecretKey, pk: &PublicKey3<K>) -> Result<K, SikeError> {
+        self.isoex2_untyped(sk, pk.as_public_key())
+    }
+
+    /// Establishing shared keys on the 3-torsion (ref `isoex_3` Algorithm 24 p.63)
+    ///  * Input: secret key, public key of the 2-torsion side, [tree traversal strategy]
+    ///  * Output: a j-invariant
+    pub fn isoex3(&self, sk: &SecretKey, pk: &PublicKey2<K>) -> Result<K, SikeError> {
+        self.isoex3_untyped(sk, pk.as_public_key())
+    }
+
+    /// Establishing shared keys on the 3-torsion with a prepared public key, as `isoex3`
+    /// without the recovery of the curve
+    pub fn isoex3_prepared(
+        &self,
+        sk: &SecretKey,
+        pk: &PreparedPublicKey<K>,
+    ) -> Result<K, SikeError> {
+        self.isoex3_with_curves(sk, &pk.pk, &pk.curve, &pk.curve_pm)
+    }
+
+    /// Steps 2, 4 to 7 of `isoex3`, from the curve of `pk` and its form (A + 2C : A - 2C)
+    fn isoex3_with_curves(
+        &self,
+        sk: &SecretKey,
+        pk: &PublicKey<K>,
+        curve: &Curve<K>,
+        curve_pm: &Curve<K>,
+    ) -> Result<K, SikeError> {
+        sk.check_length(self.params.keyspace3)?;
+
+        let one = K::one();
+        let two = one.add(&one);
+
+        // 2.
+        let (x1, x2, x3) = (&pk.x1, &pk.x2, &pk.x3);
+        let s = Self::three_pts_ladder(
+            sk.bits_lsb_first(),
+            x1.clone(),
+            x2.clone(),
+            x3.clone(),
+            curve,
+        )?;
+
         // 4.
         let (curve_pm, _) = match &self.params.e3_strategy {
-            Some(strat) => self.three_e_iso_optim(s, None, &curve_pm, &strat)?,
-            None => self.three_e_iso(s, None, &curve_pm),
+            Some(strat) => self.three_e_iso_optim(s, None, curve_pm, &strat)?,
+            None => self.three_e_iso(s, None, curve_pm),
         };
 
         // 5.
@@ -812,11 +1130,27 @@ impl<K: FiniteField + Clone + Debug> CurveIsogenies<K> {
     }
 }
 
+#[cfg(feature = "rayon")]
+impl<K: FiniteField + Clone + Debug + Send + Sync> CurveIsogenies<K> {
+    /// Computing public keys on the 3-torsion for a batch of secret keys, in parallel
+    ///  * Input: secret keys
+    ///  * Output: public keys, in the same order
+    pub fn isogen3_batch(&self, sks: &[SecretKey]) -> Vec<Result<PublicKey<K>, SikeError>> {
+        use rayon::prelude::*;
+
+        sks.par_iter().map(|sk| self.isogen3_untyped(sk)).collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
         constants::cs_p434::{SIKE_P434_NKS2, SIKE_P434_NKS3},
         ff::{PrimeFieldP434, QuadraticExtension},
+        interop::{
+            kat::{P434_KAT0_PK, P434_KAT0_SK},
+            pqcrypto::public_key_from_pqcrypto_bytes,
+        },
         isogeny::publicparams::sike_p434_params,
         utils::{
             conversion::{str_to_p434, str_to_u64},
@@ -851,22 +1185,216 @@ mod tests {
     }
 
     #[test]
-    fn test_isoex_isogen() {
-        let nks3 = str_to_u64(SIKE_P434_NKS3).unwrap();
+    fn test_iso_eval_infinity() {
         let nks2 = str_to_u64(SIKE_P434_NKS2).unwrap();
-
+        let sk = SecretKey::get_random_secret_key(nks2 as usize).unwrap();
         let params = sike_p434_params(None, None).unwrap();
 
-        let iso = CurveIsogenies::init(params);
+        let one: QuadraticExtension<PrimeFieldP434> = QuadraticExtension::one();
+        let infinity = Point {
+            x: one.clone(),
+            z: QuadraticExtension::zero(),
+        };
 
-        let sk3 = SecretKey::get_random_secret_key(nks3 as usize).unwrap();
-        let sk2 = SecretKey::get_random_secret_key(nks2 as usize).unwrap();
+        let curve = Curve::starting_curve();
+        let s = CurveIsogenies::three_pts_ladder(
+            sk.bits_lsb_first(),
+            params.xp2.clone(),
+            params.xq2.clone(),
+            params.xr2.clone(),
+            &curve,
+        )
+        .unwrap();
+
+        // Kernel point of order 4
+        let p4 = CurveIsogenies::ndouble(&s, params.e2 - 2, &curve.curve_plus());
+        let (_, k1, k2, k3) = CurveIsogenies::four_isogenous_curve(&p4);
+
+        let image = CurveIsogenies::four_isogeny_eval(&k1, &k2, &k3, &infinity);
+        assert!(image.z.is_zero() && !image.x.is_zero());
+
+        let image = CurveIsogenies::four_isogeny_eval(&k1, &k2, &k3, &p4);
+        assert!(image.z.is_zero());
+        assert!(matches!(
+            image.x.div(&image.z),
+            Err(SikeError::NotInvertible)
+        ));
+
+        let image = CurveIsogenies::three_isogeny_eval(&infinity, &k1, &k2);
+        assert!(image.z.is_zero());
+    }
 
-        let pk3 = iso.isogen3(&sk3).unwrap();
-        let pk2 = iso.isogen2(&sk2).unwrap();
+    #[test]
+    fn test_point_eq_cross_multiplication() {
+        let params = sike_p434_params(None, None).unwrap();
+        let one: QuadraticExtension<PrimeFieldP434> = QuadraticExtension::one();
+        let zero: QuadraticExtension<PrimeFieldP434> = QuadraticExtension::zero();
+
+        let by_division =
+            |p: &Point<QuadraticExtension<PrimeFieldP434>>,
+             q: &Point<QuadraticExtension<PrimeFieldP434>>| {
+                if p.z.is_zero() || q.z.is_zero() {
+                    p.z.is_zero() && q.z.is_zero()
+                } else {
+                    p.x.div(&p.z).unwrap().equals(&q.x.div(&q.z).unwrap())
+                }
+            };
+
+        let p = Point::from_x(params.xp2.clone());
+        let q = Point::from_x(params.xq2);
+        let scale = params.xp2.add(&one);
+        let p_scaled = Point {
+            x: params.xp2.mul(&scale),
+            z: scale,
+        };
+        let infinity = Point {
+            x: one.clone(),
+            z: zero.clone(),
+        };
+        let other_infinity = Point {
+            x: one.add(&one),
+            z: zero,
+        };
+
+        let points = [p, q, p_scaled, infinity, other_infinity];
+        for a in points.iter() {
+            for b in points.iter() {
+                assert_eq!(a == b, by_division(a, b));
+            }
+        }
+
+        assert_eq!(points[0], points[2]);
+        assert_ne!(points[0], points[1]);
+        assert_eq!(points[3], points[4]);
+        assert_ne!(points[0], points[3]);
+    }
+
+    #[test]
+    fn test_ndouble_with_ctx() {
+        let params = sike_p434_params(None, None).unwrap();
+        let curve = Curve::starting_curve().curve_plus();
+        let ctx = DoublingContext::new(&curve);
+
+        let mut e = [0; 1];
+        getrandom::getrandom(&mut e).unwrap();
+        let e = u64::from(e[0]) % params.e2;
+
+        let p = Point::from_x(params.xp2);
+
+        let mut reference = p.clone();
+        for _ in 0..e {
+            reference = CurveIsogenies::double(&reference, &curve);
+        }
+
+        let p_ctx = CurveIsogenies::ndouble_with_ctx(&p, e, &ctx);
+        let p_wrapped = CurveIsogenies::ndouble(&p, e, &curve);
+
+        assert_eq!(p_ctx, reference);
+        assert_eq!(p_wrapped, reference);
+    }
+
+    #[test]
+    fn test_two_iso_degree_composition() {
+        let nks2 = str_to_u64(SIKE_P434_NKS2).unwrap();
+        let sk = SecretKey::get_random_secret_key(nks2 as usize).unwrap();
+        let params = sike_p434_params(None, None).unwrap();
+
+        let curve = Curve::starting_curve();
+        let curve_plus = curve.curve_plus();
+        let s = CurveIsogenies::three_pts_ladder(
+            sk.bits_lsb_first(),
+            params.xp2.clone(),
+            params.xq2.clone(),
+            params.xr2.clone(),
+            &curve,
+        )
+        .unwrap();
+
+        // (A + 2C : 4C) -> (A : C)
+        let j_invariant = |c: Curve<QuadraticExtension<PrimeFieldP434>>| {
+            let two = QuadraticExtension::from_u64(2);
+            let four = QuadraticExtension::from_u64(4);
+            Curve::from_coeffs(c.a.mul(&four).sub(&c.c.mul(&two)), c.c)
+                .j_invariant()
+                .unwrap()
+        };
+
+        let (k1, k2) = (4, params.e2 - 4);
+
+        // Walk k1 steps with kernel [2^k2]S, carrying S along, then k2 steps with the image of S
+        let t = CurveIsogenies::ndouble(&s, k2, &curve_plus);
+        let opt = Some((s.clone(), s.clone(), s.clone()));
+        let (curve_k1, opt) = CurveIsogenies::two_iso_degree(t, k1, opt, &curve_plus);
+        let (s_k1, _, _) = opt.unwrap();
+        let (curve_k1_k2, _) = CurveIsogenies::two_iso_degree(s_k1, k2, None, &curve_k1);
+
+        let (curve_full, _) = CurveIsogenies::two_iso_degree(s, k1 + k2, None, &curve_plus);
+
+        assert_eq!(j_invariant(curve_k1_k2), j_invariant(curve_full));
+    }
+
+    #[test]
+    fn test_isogeny_walks() {
+        let nks2 = str_to_u64(SIKE_P434_NKS2).unwrap();
+        let nks3 = str_to_u64(SIKE_P434_NKS3).unwrap();
+        let sk2 = SecretKey::get_random_secret_key(nks2 as usize).unwrap();
+        let sk3 = SecretKey::get_random_secret_key(nks3 as usize).unwrap();
+        let params = sike_p434_params(None, None).unwrap();
+        let iso = CurveIsogenies::init(params.clone());
+
+        let curve = Curve::starting_curve();
+        let curve_plus = curve.curve_plus();
+        let curve_pm = curve.curve_plus_minus();
+
+        let s2 = CurveIsogenies::three_pts_ladder(
+            sk2.bits_lsb_first(),
+            params.xp2.clone(),
+            params.xq2.clone(),
+            params.xr2.clone(),
+            &curve,
+        )
+        .unwrap();
+        let s3 = CurveIsogenies::three_pts_ladder(
+            sk3.bits_lsb_first(),
+            params.xp3.clone(),
+            params.xq3.clone(),
+            params.xr3.clone(),
+            &curve,
+        )
+        .unwrap();
+
+        let steps: Vec<_> = TwoIsogenyWalk::new(s2.clone(), params.e2, &curve_plus).collect();
+        let (last, _) = steps.last().unwrap();
+        let (expected, _) = iso.two_e_iso(s2, None, &curve_plus);
+
+        assert_eq!(steps.len() as u64, (params.e2 + 1) / 2);
+        assert!(last.a.equals(&expected.a) && last.c.equals(&expected.c));
+
+        let steps: Vec<_> = ThreeIsogenyWalk::new(s3.clone(), params.e3, &curve_pm).collect();
+        let (last, _) = steps.last().unwrap();
+        let (expected, _) = iso.three_e_iso(s3, None, &curve_pm);
+
+        assert_eq!(steps.len() as u64, params.e3);
+        assert!(last.a.equals(&expected.a) && last.c.equals(&expected.c));
+    }
+
+    #[test]
+    fn test_isoex_isogen() {
+        let nks3 = str_to_u64(SIKE_P434_NKS3).unwrap();
+        let nks2 = str_to_u64(SIKE_P434_NKS2).unwrap();
+
+        let params = sike_p434_params(None, None).unwrap();
 
-        let j_a = iso.isoex2(&sk2, &pk3).unwrap();
-        let j_b = iso.isoex3(&sk3, &pk2).unwrap();
+        let iso = CurveIsogenies::init(params);
+
+        let sk3 = SecretKey::get_random_secret_key(nks3 as usize).unwrap();
+        let sk2 = SecretKey::get_random_secret_key(nks2 as usize).unwrap();
+
+        let pk3 = iso.isogen3_untyped(&sk3).unwrap();
+        let pk2 = iso.isogen2_untyped(&sk2).unwrap();
+
+        let j_a = iso.isoex2_untyped(&sk2, &pk3).unwrap();
+        let j_b = iso.isoex3_untyped(&sk3, &pk2).unwrap();
 
         println!("j_A = {:?}", j_a);
         println!("j_B = {:?}", j_b);
@@ -883,12 +1411,320 @@ mod tests {
         let params = sike_p434_params(strat, None).unwrap();
 
         let iso = CurveIsogenies::init(params);
-        let pk = iso.isogen2(&sk).unwrap();
-        let pk_2 = iso.isogen2(&sk).unwrap();
+        let pk = iso.isogen2_untyped(&sk).unwrap();
+        let pk_2 = iso.isogen2_untyped(&sk).unwrap();
 
         assert_eq!(pk, pk_2);
     }
 
+    #[test]
+    fn test_verify_isogen2() {
+        let nks2 = str_to_u64(SIKE_P434_NKS2).unwrap();
+        let sk = SecretKey::get_random_secret_key(nks2 as usize).unwrap();
+        let other_sk = SecretKey::get_random_secret_key(nks2 as usize).unwrap();
+        let strat = Some(P434_TWO_TORSION_STRATEGY.to_vec());
+
+        let params = sike_p434_params(strat, None).unwrap();
+
+        let iso = CurveIsogenies::init(params);
+        let pk = iso.isogen2_untyped(&sk).unwrap();
+
+        assert!(iso.verify_isogen2(&sk, &pk));
+        assert!(!iso.verify_isogen2(&other_sk, &pk));
+    }
+
+    #[test]
+    fn test_isogen2_curve_only() {
+        let nks2 = str_to_u64(SIKE_P434_NKS2).unwrap();
+        let sk = SecretKey::get_random_secret_key(nks2 as usize).unwrap();
+        let strat = Some(P434_TWO_TORSION_STRATEGY.to_vec());
+
+        let params = sike_p434_params(strat, None).unwrap();
+
+        let iso = CurveIsogenies::init(params);
+        let pk = iso.isogen2_untyped(&sk).unwrap();
+        let curve = iso.isogen2_curve_only(&sk).unwrap();
+
+        let j = curve.j_invariant().unwrap();
+        let j_pk = Curve::from_public_key(&pk).unwrap().j_invariant().unwrap();
+
+        assert!(j.equals(&j_pk));
+    }
+
+    #[test]
+    fn test_public_key_torsion_points() {
+        let nks2 = str_to_u64(SIKE_P434_NKS2).unwrap();
+        let nks3 = str_to_u64(SIKE_P434_NKS3).unwrap();
+        let sk2 = SecretKey::get_random_secret_key(nks2 as usize).unwrap();
+        let sk3 = SecretKey::get_random_secret_key(nks3 as usize).unwrap();
+
+        let params = sike_p434_params(None, None).unwrap();
+        let iso = CurveIsogenies::init(params);
+
+        let pk2 = iso.isogen2_untyped(&sk2).unwrap();
+        let named = PublicKey::from_torsion_points(
+            pk2.x_p().clone(),
+            pk2.x_q().clone(),
+            pk2.x_qmp().clone(),
+        );
+        assert_eq!(named, pk2);
+
+        let j = iso.isoex3_untyped(&sk3, &pk2).unwrap();
+        let j_named = iso.isoex3_untyped(&sk3, &named).unwrap();
+        assert!(j.equals(&j_named));
+
+        // Transposing P and Q changes the shared curve
+        let transposed = PublicKey::from_torsion_points(
+            pk2.x_q().clone(),
+            pk2.x_p().clone(),
+            pk2.x_qmp().clone(),
+        );
+        let j_transposed = iso.isoex3_untyped(&sk3, &transposed).unwrap();
+        assert!(!j.equals(&j_transposed));
+    }
+
+    #[test]
+    fn test_secret_key_too_long() {
+        let nks2 = str_to_u64(SIKE_P434_NKS2).unwrap();
+        let nks3 = str_to_u64(SIKE_P434_NKS3).unwrap();
+        let params = sike_p434_params(None, None).unwrap();
+        let iso = CurveIsogenies::init(params);
+
+        // A key for a larger parameter set
+        let sk = SecretKey::get_random_secret_key(nks3 as usize + 1).unwrap();
+
+        match iso.isogen3_untyped(&sk) {
+            Err(SikeError::InvalidSecretKeyLength { len, keyspace }) => {
+                assert_eq!(len, nks3 as usize + 1);
+                assert_eq!(keyspace, nks3 as usize);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let sk = SecretKey::get_random_secret_key(nks2 as usize + 1).unwrap();
+        assert!(matches!(
+            iso.isogen2_untyped(&sk),
+            Err(SikeError::InvalidSecretKeyLength { .. })
+        ));
+    }
+
+    #[test]
+    fn test_strategy_out_of_range() {
+        let nks2 = str_to_u64(SIKE_P434_NKS2).unwrap();
+        let nks3 = str_to_u64(SIKE_P434_NKS3).unwrap();
+        let sk2 = SecretKey::get_random_secret_key(nks2 as usize).unwrap();
+        let sk3 = SecretKey::get_random_secret_key(nks3 as usize).unwrap();
+
+        // An entry as large as the tree, then a zero entry
+        let mut strat2 = P434_TWO_TORSION_STRATEGY.to_vec();
+        strat2[0] = 108;
+        let mut strat3 = P434_THREE_TORSION_STRATEGY.to_vec();
+        strat3[5] = 0;
+
+        let iso = CurveIsogenies::init(
+            sike_p434_params(Some(strat2.clone()), Some(strat3.clone())).unwrap(),
+        );
+        assert!(matches!(
+            iso.isogen2_untyped(&sk2),
+            Err(SikeError::InvalidStrategy)
+        ));
+        assert!(matches!(
+            iso.isogen3_untyped(&sk3),
+            Err(SikeError::InvalidStrategy)
+        ));
+
+        // The shapes are checked before the walks, which also reject them on their own
+        let params = &iso.params;
+        let s2 = CurveIsogenies::three_pts_ladder(
+            sk2.bits_lsb_first(),
+            params.xp2.clone(),
+            params.xq2.clone(),
+            params.xr2.clone(),
+            &iso.start_curve,
+        )
+        .unwrap();
+        let walk2 = iso.two_e_iso_optim_unchecked(s2, None, &iso.start_curve_plus, &strat2);
+        assert!(matches!(walk2, Err(SikeError::InvalidStrategy)));
+
+        let s3 = CurveIsogenies::three_pts_ladder(
+            sk3.bits_lsb_first(),
+            params.xp3.clone(),
+            params.xq3.clone(),
+            params.xr3.clone(),
+            &iso.start_curve,
+        )
+        .unwrap();
+        let walk3 = iso.three_e_iso_optim_unchecked(s3, None, &iso.start_curve_pm, &strat3);
+        assert!(matches!(walk3, Err(SikeError::InvalidStrategy)));
+    }
+
+    #[test]
+    fn test_benchmark_walk() {
+        let nks2 = str_to_u64(SIKE_P434_NKS2).unwrap();
+        let sk = SecretKey::get_random_secret_key(nks2 as usize).unwrap();
+
+        let params = sike_p434_params(None, None).unwrap();
+        let iso = CurveIsogenies::init(params);
+        assert!(matches!(
+            iso.benchmark_walk(&sk, 1),
+            Err(SikeError::InvalidStrategy)
+        ));
+
+        let strat = Some(P434_TWO_TORSION_STRATEGY.to_vec());
+        let params = sike_p434_params(strat, None).unwrap();
+        let iso = CurveIsogenies::init(params);
+
+        // Timings vary with the machine, the comparison itself is in the `keygen` bench group
+        let report = iso.benchmark_walk(&sk, 3).unwrap();
+        assert_eq!(report.iterations, 3);
+        assert!(report.simple > Duration::from_secs(0));
+        assert!(report.optimized > Duration::from_secs(0));
+        assert!(report.speedup().is_finite() && report.speedup() > 0.0);
+    }
+
+    #[test]
+    fn test_isogen2_projective() {
+        let nks2 = str_to_u64(SIKE_P434_NKS2).unwrap();
+        let sk = SecretKey::get_random_secret_key(nks2 as usize).unwrap();
+        let strat = Some(P434_TWO_TORSION_STRATEGY.to_vec());
+
+        let params = sike_p434_params(strat, None).unwrap();
+        let iso = CurveIsogenies::init(params);
+
+        let pk = iso.isogen2_untyped(&sk).unwrap();
+        let projective = iso.isogen2_projective(&sk).unwrap();
+
+        assert_eq!(projective.to_affine().unwrap(), pk);
+        assert_eq!(projective.to_affine_unbatched().unwrap(), pk);
+    }
+
+    #[test]
+    fn test_public_key_affine_projective() {
+        let params = sike_p434_params(None, None).unwrap();
+        let pk = PublicKey::from_affine_coords(params.xp2, params.xq2, params.xr2);
+
+        let [p1, p2, p3] = pk.to_projective();
+        assert!(p1.1.equals(&QuadraticExtension::one()));
+        let projective = ProjectivePublicKey { p1, p2, p3 };
+        assert_eq!(projective.to_affine().unwrap(), pk);
+
+        // Any representative of the points normalises to the same key
+        let z = QuadraticExtension::from_u64(5);
+        let scale = |(x, z_p): (_, _)| (z.mul(&x), z.mul(&z_p));
+        let [p1, p2, p3] = pk.to_projective().map(scale);
+        let projective = ProjectivePublicKey { p1, p2, p3 };
+        assert_eq!(projective.to_affine().unwrap(), pk);
+    }
+
+    #[test]
+    fn test_public_key_same_curve() {
+        let params = sike_p434_params(None, None).unwrap();
+        let iso = CurveIsogenies::init(params.clone());
+
+        let sk = SecretKey::get_random_secret_key(params.keyspace3 as usize).unwrap();
+        let other_sk = SecretKey::get_random_secret_key(params.keyspace3 as usize).unwrap();
+        let pk = iso.isogen3_untyped(&sk).unwrap();
+        let other_pk = iso.isogen3_untyped(&other_sk).unwrap();
+
+        // x ↦ -x maps the points onto y² = x³ - Ax² + x, a different coefficient
+        // with the same j-invariant
+        let negated = PublicKey::from_torsion_points(pk.x1.neg(), pk.x2.neg(), pk.x3.neg());
+        assert_ne!(pk, negated);
+        assert!(!Curve::from_public_key(&pk)
+            .unwrap()
+            .a
+            .equals(&Curve::from_public_key(&negated).unwrap().a));
+
+        assert!(pk.same_curve(&pk).unwrap());
+        assert!(pk.same_curve(&negated).unwrap());
+        assert!(!pk.same_curve(&other_pk).unwrap());
+
+        let zero = pk.x1.sub(&pk.x1);
+        let invalid = PublicKey::from_torsion_points(zero, pk.x2.clone(), pk.x3.clone());
+        assert!(pk.same_curve(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_isoex_prepared() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+        let iso = CurveIsogenies::init(params.clone());
+
+        let sk2 = SecretKey::get_random_secret_key(params.keyspace2 as usize).unwrap();
+        let sk3 = SecretKey::get_random_secret_key(params.keyspace3 as usize).unwrap();
+        let pk2 = iso.isogen2_untyped(&sk2).unwrap();
+        let pk3 = iso.isogen3_untyped(&sk3).unwrap();
+
+        let prepared2 = PreparedPublicKey::new(pk2.clone()).unwrap();
+        let prepared3 = PreparedPublicKey::new(pk3.clone()).unwrap();
+        assert_eq!(prepared3.public_key(), &pk3);
+
+        let j2 = iso.isoex2_untyped(&sk2, &pk3).unwrap();
+        let j3 = iso.isoex3_untyped(&sk3, &pk2).unwrap();
+        assert!(j2.equals(&j3));
+        assert!(iso.isoex2_prepared(&sk2, &prepared3).unwrap().equals(&j2));
+        assert!(iso.isoex3_prepared(&sk3, &prepared2).unwrap().equals(&j3));
+
+        // Keys that do not define a curve cannot be prepared
+        let zero = pk3.x1.sub(&pk3.x1);
+        let invalid = PublicKey::from_torsion_points(zero, pk3.x2, pk3.x3);
+        assert!(PreparedPublicKey::new(invalid).is_err());
+    }
+
+    #[test]
+    fn test_isoex_odd_e2_p610() {
+        use crate::{
+            isogeny::publicparams::sike_p610_params,
+            utils::strategy::{P610_THREE_TORSION_STRATEGY, P610_TWO_TORSION_STRATEGY},
+        };
+
+        let simple = sike_p610_params(None, None).unwrap();
+        let optim = sike_p610_params(
+            Some(P610_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P610_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+        assert_eq!(optim.e2 % 2, 1);
+
+        let iso_simple = CurveIsogenies::init(simple);
+        let iso = CurveIsogenies::init(optim.clone());
+
+        let sk2 = SecretKey::get_random_secret_key(optim.keyspace2 as usize).unwrap();
+        let sk3 = SecretKey::get_random_secret_key(optim.keyspace3 as usize).unwrap();
+        let pk2 = iso.isogen2_untyped(&sk2).unwrap();
+        let pk3 = iso.isogen3_untyped(&sk3).unwrap();
+        assert_eq!(pk2, iso_simple.isogen2_untyped(&sk2).unwrap());
+
+        // The peeled 2-isogeny of `isoex2` starts from the curve recovered from `pk3`
+        let j2 = iso.isoex2_untyped(&sk2, &pk3).unwrap();
+        assert!(j2.equals(&iso_simple.isoex2_untyped(&sk2, &pk3).unwrap()));
+        assert!(j2.equals(&iso.isoex3_untyped(&sk3, &pk2).unwrap()));
+    }
+
+    #[test]
+    fn test_isoex_typed() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+        let iso = CurveIsogenies::init(params.clone());
+
+        let sk2 = SecretKey::get_random_secret_key(params.keyspace2 as usize).unwrap();
+        let sk3 = SecretKey::get_random_secret_key(params.keyspace3 as usize).unwrap();
+        let pk2: PublicKey2<_> = iso.isogen2(&sk2).unwrap();
+        let pk3: PublicKey3<_> = iso.isogen3(&sk3).unwrap();
+        assert_eq!(pk2.as_public_key(), &iso.isogen2_untyped(&sk2).unwrap());
+        assert_eq!(pk3.clone().into_inner(), iso.isogen3_untyped(&sk3).unwrap());
+
+        let j2 = iso.isoex2(&sk2, &pk3).unwrap();
+        let j3 = iso.isoex3(&sk3, &pk2).unwrap();
+        assert!(j2.equals(&j3));
+    }
+
     #[test]
     fn test_isogen3() {
         let nks3 = str_to_u64(SIKE_P434_NKS3).unwrap();
@@ -898,12 +1734,189 @@ mod tests {
         let params = sike_p434_params(None, strat).unwrap();
 
         let iso = CurveIsogenies::init(params);
-        let pk = iso.isogen3(&sk).unwrap();
-        let pk_2 = iso.isogen3(&sk).unwrap();
+        let pk = iso.isogen3_untyped(&sk).unwrap();
+        let pk_2 = iso.isogen3_untyped(&sk).unwrap();
 
         assert_eq!(pk, pk_2);
     }
 
+    #[test]
+    fn test_three_pts_ladder_bit_order() {
+        let params = sike_p434_params(None, None).unwrap();
+        let curve = Curve::starting_curve();
+        let (xp, xq, xr) = (&params.xp2, &params.xq2, &params.xr2);
+
+        let ladder = |bytes: &[u8]| {
+            CurveIsogenies::three_pts_ladder(
+                SecretKey::from_bytes(bytes).bits_lsb_first(),
+                xp.clone(),
+                xq.clone(),
+                xr.clone(),
+                &curve,
+            )
+            .unwrap()
+        };
+
+        // x(P + Q) x(Q - P) = (x_P x_Q - 1)² / (x_P - x_Q)²
+        let num = xp.mul(xq).sub(&QuadraticExtension::one());
+        let den = xp.sub(xq);
+        let x_ppq = num.mul(&num).div(&den.mul(&den).mul(xr)).unwrap();
+        let p_plus_q = Point::from_x(x_ppq);
+
+        // The scalar is read in little endian, as the integer of the specification
+        assert_eq!(ladder(&[0]), Point::from_x(xp.clone()));
+        assert_eq!(ladder(&[1]), p_plus_q);
+        assert_eq!(ladder(&[1, 0, 0]), p_plus_q);
+        assert_ne!(ladder(&[0, 0, 1]), p_plus_q);
+    }
+
+    #[test]
+    fn test_isogen3_known_answer() {
+        // sk3 is the little-endian scalar after the 16 bytes of s in the reference secret key
+        let sk3 = SecretKey::from_bytes(&hex::decode(P434_KAT0_SK).unwrap()[16..44]);
+        let pk = public_key_from_pqcrypto_bytes(&hex::decode(P434_KAT0_PK).unwrap()).unwrap();
+
+        let iso = CurveIsogenies::init(sike_p434_params(None, None).unwrap());
+        assert_eq!(iso.isogen3_untyped(&sk3).unwrap(), pk);
+
+        let strat = Some(P434_THREE_TORSION_STRATEGY.to_vec());
+        let iso = CurveIsogenies::init(sike_p434_params(None, strat).unwrap());
+        assert_eq!(iso.isogen3_untyped(&sk3).unwrap(), pk);
+    }
+
+    #[test]
+    fn test_secret_key_minimal_bytes() {
+        let params = sike_p434_params(None, None).unwrap();
+        let iso = CurveIsogenies::init(params.clone());
+        let curve = Curve::starting_curve();
+
+        // A 200-bit scalar stored in the 27 bytes of the key space, and in 25 bytes
+        let random = SecretKey::get_random_secret_key(params.keyspace2 as usize).unwrap();
+        let mut full = SecretKey::from_bytes_clamped(&random.to_bytes(), 200).to_bytes();
+        full.resize(params.keyspace2 as usize, 0);
+        let full = SecretKey::from_bytes(&full);
+
+        let minimal = SecretKey::from_bytes(&full.minimal_bytes(200).unwrap());
+        assert_eq!(minimal.to_bytes().len(), 25);
+        assert_eq!(minimal.to_integer(), full.to_integer());
+        assert_eq!(
+            minimal.minimal_bytes(216).unwrap(),
+            full.minimal_bytes(216).unwrap()
+        );
+
+        let ladder = |sk: &SecretKey| {
+            CurveIsogenies::three_pts_ladder(
+                sk.bits_lsb_first(),
+                params.xp2.clone(),
+                params.xq2.clone(),
+                params.xr2.clone(),
+                &curve,
+            )
+            .unwrap()
+        };
+        assert_eq!(ladder(&minimal), ladder(&full));
+        assert_eq!(
+            iso.isogen2_untyped(&minimal).unwrap(),
+            iso.isogen2_untyped(&full).unwrap()
+        );
+
+        // Non-zero bytes are never dropped
+        assert!(SecretKey::from_bytes(&[1, 0, 2]).minimal_bytes(16).is_err());
+    }
+
+    #[test]
+    fn test_isogen_cached_starting_curve() {
+        let nks2 = str_to_u64(SIKE_P434_NKS2).unwrap();
+        let sk = SecretKey::get_random_secret_key(nks2 as usize).unwrap();
+
+        let params = sike_p434_params(None, None).unwrap();
+        let iso = CurveIsogenies::init(params.clone());
+        let pk = iso.isogen2_untyped(&sk).unwrap();
+
+        // Recompute the starting curve forms instead of using the cached ones
+        let curve = Curve::starting_curve();
+        let curve_plus = curve.curve_plus();
+        let s = CurveIsogenies::three_pts_ladder(
+            sk.bits_lsb_first(),
+            params.xp2,
+            params.xq2,
+            params.xr2,
+            &curve,
+        )
+        .unwrap();
+        let opt = Some((
+            Point::from_x(params.xp3),
+            Point::from_x(params.xq3),
+            Point::from_x(params.xr3),
+        ));
+        let (_, opt) = iso.two_e_iso(s, opt, &curve_plus);
+        let (p1, p2, p3) = opt.unwrap();
+
+        assert!(pk.x1.equals(&p1.x.div(&p1.z).unwrap()));
+        assert!(pk.x2.equals(&p2.x.div(&p2.z).unwrap()));
+        assert!(pk.x3.equals(&p3.x.div(&p3.z).unwrap()));
+
+        assert!(iso.start_curve_plus.a.equals(&curve_plus.a));
+        assert!(iso.start_curve_plus.c.equals(&curve_plus.c));
+        assert!(iso.start_curve_pm.a.equals(&curve.curve_plus_minus().a));
+        assert!(iso.start_curve_pm.c.equals(&curve.curve_plus_minus().c));
+    }
+
+    #[test]
+    fn test_extend_key() {
+        let nks3 = str_to_u64(SIKE_P434_NKS3).unwrap();
+        let sk = SecretKey::get_random_secret_key(nks3 as usize).unwrap();
+        let delta = SecretKey::get_random_secret_key(nks3 as usize).unwrap();
+        let zero = SecretKey::from_bytes(&[0; 1]);
+        let strat = Some(P434_THREE_TORSION_STRATEGY.to_vec());
+
+        let params = sike_p434_params(None, strat).unwrap();
+        let order = params.cofactor_2().unwrap();
+
+        let iso = CurveIsogenies::init(params);
+
+        let pk = iso.extend_key(&sk, &delta).unwrap();
+        let expected = iso.isogen3_untyped(&sk.add_mod(&delta, &order)).unwrap();
+        assert_eq!(pk, expected);
+
+        // sk + 0 = sk mod 3^e3
+        let sk_reduced = sk.add_mod(&zero, &order);
+        let pk_sk = iso.isogen3_untyped(&sk).unwrap();
+        assert_eq!(iso.extend_key(&sk, &zero).unwrap(), pk_sk);
+        assert_eq!(iso.isogen3_untyped(&sk_reduced).unwrap(), pk_sk);
+
+        let a = SecretKey::from_bytes(&[250, 1]);
+        let b = SecretKey::from_bytes(&[10]);
+        let sum = a.add_mod(&b, &rug::Integer::from(1000));
+        assert_eq!(*sum.to_bytes(), vec![4, 2]);
+
+        let mut params = sike_p434_params(None, None).unwrap();
+        params.e3 += 1 << 32;
+        assert!(matches!(
+            CurveIsogenies::init(params).extend_key(&sk, &delta),
+            Err(SikeError::InconsistentParameters)
+        ));
+    }
+
+    #[test]
+    fn test_isogen3_batch() {
+        let nks3 = str_to_u64(SIKE_P434_NKS3).unwrap();
+        let sks: Vec<SecretKey> = (0..4)
+            .map(|_| SecretKey::get_random_secret_key(nks3 as usize).unwrap())
+            .collect();
+        let strat = Some(P434_THREE_TORSION_STRATEGY.to_vec());
+
+        let params = sike_p434_params(None, strat).unwrap();
+
+        let iso = CurveIsogenies::init(params);
+        let pks = iso.isogen3_batch(&sks);
+
+        assert_eq!(pks.len(), sks.len());
+        for (sk, pk) in sks.iter().zip(pks) {
+            assert_eq!(pk.unwrap(), iso.isogen3_untyped(sk).unwrap());
+        }
+    }
+
     #[test]
     fn test_conversion_secretkey_bytes() {
         let k = SecretKey::get_random_secret_key(256).unwrap();
@@ -913,6 +1926,27 @@ mod tests {
         assert_eq!(k, k_recovered);
     }
 
+    #[test]
+    fn test_isogen3_clamped_key() {
+        let params = sike_p434_params(None, None).unwrap();
+        let iso = CurveIsogenies::init(params.clone());
+
+        // sk3 lies in [0, 2^217), on 28 bytes
+        let bytes = [0xff; 28];
+        let clamped = SecretKey::from_bytes_clamped(&bytes, 217);
+        let n = (rug::Integer::from(1) << 217) - 1;
+        let expected = SecretKey::from_integer(&n, params.keyspace3 as usize).unwrap();
+
+        assert_eq!(
+            iso.isogen3_untyped(&clamped).unwrap(),
+            iso.isogen3_untyped(&expected).unwrap()
+        );
+        assert_ne!(
+            iso.isogen3_untyped(&clamped).unwrap(),
+            iso.isogen3_untyped(&SecretKey::from_bytes(&bytes)).unwrap()
+        );
+    }
+
     #[test]
     fn test_conversion_publickey_bytes() {
         let nks3 = str_to_u64(SIKE_P434_NKS3).unwrap();
@@ -921,7 +1955,7 @@ mod tests {
 
         let params = sike_p434_params(None, strat).unwrap();
         let iso = CurveIsogenies::init(params);
-        let pk = iso.isogen3(&sk).unwrap();
+        let pk = iso.isogen3_untyped(&sk).unwrap();
         let (b0, b1, b2) = pk.clone().into_bytes();
 
         let pk_recovered = PublicKey::from_bytes(&b0, &b1, &b2).unwrap();
@@ -929,6 +1963,197 @@ mod tests {
         assert_eq!(pk, pk_recovered)
     }
 
+    #[test]
+    fn test_fixed_publickey_p434() {
+        let nks3 = str_to_u64(SIKE_P434_NKS3).unwrap();
+        let sk = SecretKey::get_random_secret_key(nks3 as usize).unwrap();
+        let params = sike_p434_params(None, None).unwrap();
+
+        let iso = CurveIsogenies::init(params);
+        let pk = iso.isogen3_untyped(&sk).unwrap();
+
+        let fixed = FixedPublicKeyP434::from_public_key(pk.clone()).unwrap();
+        let array: [u8; 330] = fixed.to_array();
+
+        let recovered = FixedPublicKeyP434::from_array(array)
+            .to_public_key::<PrimeFieldP434>()
+            .unwrap();
+
+        assert_eq!(pk, recovered);
+    }
+
+    #[test]
+    fn test_estimated_keygen_muls() {
+        use crate::{
+            isogeny::publicparams::{sike_p503_params, sike_p610_params, sike_p751_params},
+            utils::strategy::*,
+        };
+
+        let estimates = [
+            sike_p434_params(None, Some(P434_THREE_TORSION_STRATEGY.to_vec()))
+                .unwrap()
+                .estimated_keygen_muls(),
+            sike_p503_params(None, Some(P503_THREE_TORSION_STRATEGY.to_vec()))
+                .unwrap()
+                .estimated_keygen_muls(),
+            sike_p610_params(None, Some(P610_THREE_TORSION_STRATEGY.to_vec()))
+                .unwrap()
+                .estimated_keygen_muls(),
+            sike_p751_params(None, Some(P751_THREE_TORSION_STRATEGY.to_vec()))
+                .unwrap()
+                .estimated_keygen_muls(),
+        ];
+
+        assert!(estimates.windows(2).all(|w| w[0] < w[1]));
+
+        // The strategy pays off
+        let simple = sike_p434_params(None, None).unwrap();
+        assert!(simple.estimated_keygen_muls() > estimates[0]);
+
+        // Degenerate parameters do not underflow
+        let mut empty = sike_p434_params(None, None).unwrap();
+        empty.e3 = 0;
+        empty.keyspace3 = 0;
+        assert_eq!(empty.estimated_keygen_muls(), 0);
+
+        let mut malformed = sike_p434_params(None, None).unwrap();
+        malformed.e3_strategy = Some(vec![0; 136]);
+        assert_eq!(malformed.estimated_keygen_muls(), u64::MAX);
+    }
+
+    #[test]
+    fn test_cofactors() {
+        use crate::{
+            constants::{cs_p434::*, cs_p503::*, cs_p610::*, cs_p751::*},
+            isogeny::publicparams::{sike_p503_params, sike_p610_params, sike_p751_params},
+        };
+        use rug::Integer;
+
+        fn cofactors<K>(params: PublicParameters<K>) -> (u64, u64, Integer, Integer) {
+            (
+                params.e2,
+                params.e3,
+                params.cofactor_2().unwrap(),
+                params.cofactor_3().unwrap(),
+            )
+        }
+
+        let sets = vec![
+            cofactors(sike_p434_params(None, None).unwrap()),
+            cofactors(sike_p503_params(None, None).unwrap()),
+            cofactors(sike_p610_params(None, None).unwrap()),
+            cofactors(sike_p751_params(None, None).unwrap()),
+        ];
+        let primes = [SIKE_P434_P, SIKE_P503_P, SIKE_P610_P, SIKE_P751_P];
+
+        for ((e2, e3, cofactor_2, cofactor_3), p) in sets.into_iter().zip(primes.iter()) {
+            let p_plus_one = Integer::from_str_radix(p, 16).unwrap() + 1u32;
+            assert_eq!(cofactor_2 << e2 as u32, p_plus_one);
+            assert_eq!(
+                cofactor_3 * Integer::from(Integer::u_pow_u(3, e3 as u32)),
+                p_plus_one
+            );
+        }
+    }
+
+    #[test]
+    fn test_public_key_byte_len() {
+        use crate::{
+            isogeny::publicparams::{sike_p503_params, sike_p610_params, sike_p751_params},
+            utils::strategy::*,
+        };
+
+        macro_rules! check_len {
+            ($params:expr, $fixed:ty, $set:expr) => {{
+                let params = $params.unwrap();
+                let len = params.public_key_byte_len().unwrap();
+                assert_eq!(len, $set.public_key_len());
+
+                let sk = SecretKey::get_random_secret_key(params.keyspace3 as usize).unwrap();
+                let pk = CurveIsogenies::init(params).isogen3_untyped(&sk).unwrap();
+                assert_eq!(len, <$fixed>::from_public_key(pk).unwrap().to_array().len());
+            }};
+        }
+
+        check_len!(
+            sike_p434_params(None, Some(P434_THREE_TORSION_STRATEGY.to_vec())),
+            FixedPublicKeyP434,
+            SikeParameterSet::P434
+        );
+        check_len!(
+            sike_p503_params(None, Some(P503_THREE_TORSION_STRATEGY.to_vec())),
+            FixedPublicKeyP503,
+            SikeParameterSet::P503
+        );
+        check_len!(
+            sike_p610_params(None, Some(P610_THREE_TORSION_STRATEGY.to_vec())),
+            FixedPublicKeyP610,
+            SikeParameterSet::P610
+        );
+        check_len!(
+            sike_p751_params(None, Some(P751_THREE_TORSION_STRATEGY.to_vec())),
+            FixedPublicKeyP751,
+            SikeParameterSet::P751
+        );
+
+        let mut params = sike_p434_params(None, None).unwrap();
+        params.e3 += 1 << 32;
+        assert!(matches!(
+            params.public_key_byte_len(),
+            Err(SikeError::InconsistentParameters)
+        ));
+    }
+
+    #[test]
+    fn test_detect_parameter_set() {
+        assert_eq!(detect_parameter_set(330), Some(SikeParameterSet::P434));
+        assert_eq!(detect_parameter_set(378), Some(SikeParameterSet::P503));
+        assert_eq!(detect_parameter_set(462), Some(SikeParameterSet::P610));
+        assert_eq!(detect_parameter_set(564), Some(SikeParameterSet::P751));
+
+        assert_eq!(detect_parameter_set(0), None);
+        assert_eq!(detect_parameter_set(329), None);
+        assert_eq!(detect_parameter_set(2 * 330), None);
+    }
+
+    #[test]
+    fn test_publickey_fingerprint() {
+        let nks3 = str_to_u64(SIKE_P434_NKS3).unwrap();
+        let sk = SecretKey::get_random_secret_key(nks3 as usize).unwrap();
+        let other_sk = SecretKey::get_random_secret_key(nks3 as usize).unwrap();
+        let strat = Some(P434_THREE_TORSION_STRATEGY.to_vec());
+
+        let params = sike_p434_params(None, strat).unwrap();
+        let iso = CurveIsogenies::init(params);
+        let pk = iso.isogen3_untyped(&sk).unwrap();
+        let other_pk = iso.isogen3_untyped(&other_sk).unwrap();
+
+        assert_eq!(pk.fingerprint(), pk.clone().fingerprint());
+        assert_eq!(pk.fingerprint_hex(), hex::encode(pk.fingerprint()));
+        assert_ne!(pk.fingerprint(), other_pk.fingerprint());
+    }
+
+    #[test]
+    fn test_publickey_is_trivial() {
+        let params = sike_p434_params(None, None).unwrap();
+        let iso = CurveIsogenies::init(params.clone());
+
+        // The unmoved basis is on the starting curve
+        let basis = PublicKey::from_torsion_points(
+            params.xp3.clone(),
+            params.xq3.clone(),
+            params.xr3.clone(),
+        );
+        assert!(basis.is_trivial(&params));
+
+        // A zero scalar still walks a full-degree isogeny, of kernel <P>
+        let zero = SecretKey::from_bytes(&vec![0; params.keyspace3 as usize]);
+        assert!(!iso.isogen3_untyped(&zero).unwrap().is_trivial(&params));
+
+        let sk = SecretKey::get_random_secret_key(params.keyspace3 as usize).unwrap();
+        assert!(!iso.isogen3_untyped(&sk).unwrap().is_trivial(&params));
+    }
+
     #[test]
     fn test_j_invariant() {
         use crate::{
@@ -942,4 +2167,188 @@ mod tests {
         // 287496 + 0i
         assert_eq!(j, str_to_p434("00046308", "00000000").unwrap())
     }
+
+    #[test]
+    fn test_j_invariant_base_field() {
+        type K = QuadraticExtension<PrimeFieldP434>;
+
+        // j(E_6) = 287496 is rational, the public key curves are not
+        let j: K = Curve::starting_curve().j_invariant().unwrap();
+        assert!(j.is_base_field());
+        assert!(j
+            .base_field_part()
+            .unwrap()
+            .equals(&PrimeFieldP434::from_u64(287496)));
+
+        let params = sike_p434_params(None, None).unwrap();
+        let sk = SecretKey::get_random_secret_key(params.keyspace3 as usize).unwrap();
+        let pk = CurveIsogenies::init(params).isogen3_untyped(&sk).unwrap();
+        let j = Curve::from_public_key(&pk).unwrap().j_invariant().unwrap();
+        assert!(!j.is_base_field());
+        assert!(j.base_field_part().is_none());
+    }
+
+    #[test]
+    fn test_curve_twist() {
+        let nks3 = str_to_u64(SIKE_P434_NKS3).unwrap();
+        let sk = SecretKey::get_random_secret_key(nks3 as usize).unwrap();
+        let params = sike_p434_params(None, None).unwrap();
+
+        let pk = CurveIsogenies::init(params).isogen3_untyped(&sk).unwrap();
+        let curve = Curve::from_public_key(&pk).unwrap();
+        let twist = curve.twist();
+        let twist_twist = twist.twist();
+
+        // Projective equality of (A : C)
+        assert!(twist_twist
+            .a
+            .mul(&curve.c)
+            .equals(&curve.a.mul(&twist_twist.c)));
+        assert!(!twist.a.mul(&curve.c).equals(&curve.a.mul(&twist.c)));
+
+        let j = curve.j_invariant().unwrap();
+        assert!(j.equals(&twist.j_invariant().unwrap()));
+        assert!(j.equals(&twist_twist.j_invariant().unwrap()));
+
+        // x ↦ -x maps the curve to its twist, -1 being a square in 𝔽ₚ(i)
+        let p = curve.hash_to_point(b"twist").unwrap();
+        assert!(twist.is_x_on_curve(&p.x.neg()));
+    }
+
+    #[test]
+    fn test_curve_a24() {
+        type K = QuadraticExtension<PrimeFieldP434>;
+        let curve: Curve<K> = Curve::starting_curve();
+        let two = K::one().add(&K::one());
+
+        // A = 6, C = 1: (A + 2C) / 4C = 2 and (A - 2C) / 4C = 1
+        assert!(curve.a24_plus().unwrap().equals(&two));
+        assert!(curve.a24_minus().unwrap().equals(&K::one()));
+
+        // Both are invariant under scaling (A : C)
+        let scaled = Curve::from_coeffs(curve.a.mul(&two), curve.c.mul(&two));
+        assert!(scaled.a24_plus().unwrap().equals(&two));
+        assert!(scaled.a24_minus().unwrap().equals(&K::one()));
+    }
+
+    #[test]
+    fn test_two_isogeny_from_kernel_x() {
+        let params = sike_p434_params(None, None).unwrap();
+        let curve = Curve::starting_curve();
+        let curve_plus = curve.curve_plus();
+
+        // Kernel of order 2 from P2, and a point to map
+        let p2 = Point::from_x(params.xp2.clone());
+        let kernel = CurveIsogenies::ndouble(&p2, params.e2 - 1, &curve_plus);
+        let q = Point::from_x(params.xq3.clone());
+
+        let x = kernel.x.div(&kernel.z).unwrap();
+        let (image, phi) = curve.two_isogeny_from_kernel_x(&x);
+
+        let expected = CurveIsogenies::two_isogenous_curve(&kernel);
+        assert!(image.a.mul(&expected.c).equals(&expected.a.mul(&image.c)));
+        assert_eq!(phi(&q), CurveIsogenies::two_isogeny_eval(&kernel, &q));
+    }
+
+    #[test]
+    fn test_curve_a_coefficient_bytes() {
+        type K = QuadraticExtension<PrimeFieldP434>;
+        let curve: Curve<K> = Curve::starting_curve();
+        let seven = K::from_u64(7);
+
+        let scaled = Curve::from_coeffs(curve.a.mul(&seven), curve.c.mul(&seven));
+        assert_eq!(
+            curve.a_coefficient_bytes().unwrap(),
+            scaled.a_coefficient_bytes().unwrap()
+        );
+        assert_eq!(
+            curve.a_coefficient_bytes().unwrap(),
+            K::from_u64(6).into_bytes()
+        );
+
+        assert_ne!(
+            curve.a_coefficient_bytes().unwrap(),
+            curve.twist().a_coefficient_bytes().unwrap()
+        );
+        assert!(Curve::from_coeffs(K::one(), K::zero())
+            .a_coefficient_bytes()
+            .is_err());
+    }
+
+    #[test]
+    fn test_hash_to_point() {
+        let curve: Curve<QuadraticExtension<PrimeFieldP434>> = Curve::starting_curve();
+
+        let p = curve.hash_to_point(b"rust-sike").unwrap();
+        let p_again = curve.hash_to_point(b"rust-sike").unwrap();
+        let q = curve.hash_to_point(b"rust-sike!").unwrap();
+
+        assert_eq!(p, p_again);
+        assert_ne!(p, q);
+        assert!(curve.is_x_on_curve(&p.x));
+        assert!(curve.is_x_on_curve(&q.x));
+
+        // Well within the default limit on the starting curve, and capped otherwise
+        for i in 0u8..32 {
+            assert!(curve.hash_to_point_with_limit(&[i], 16).is_ok());
+        }
+        assert!(matches!(
+            curve.hash_to_point_with_limit(b"rust-sike", 0),
+            Err(SikeError::SamplingFailed)
+        ));
+    }
+
+    #[test]
+    fn test_from_public_key_zero_denominator() {
+        use crate::constants::cs_p434::SIKE_P434_P;
+
+        let params = sike_p434_params(None, None).unwrap();
+
+        // p is not reduced, so it is nonzero as an encoding but makes 4 x_p x_q x_r vanish
+        let pk = PublicKey {
+            x1: str_to_p434(SIKE_P434_P, "00").unwrap(),
+            x2: params.xq2.clone(),
+            x3: params.xr2.clone(),
+        };
+
+        match Curve::from_public_key(&pk) {
+            Err(SikeError::InvalidPublicKey) => {}
+            other => panic!("unexpected result: {:?}", other.map(|c| c.a)),
+        }
+
+        let nks3 = str_to_u64(SIKE_P434_NKS3).unwrap();
+        let sk = SecretKey::get_random_secret_key(nks3 as usize).unwrap();
+        let iso = CurveIsogenies::init(params);
+
+        assert!(matches!(
+            iso.isoex3_untyped(&sk, &pk),
+            Err(SikeError::InvalidPublicKey)
+        ));
+    }
+
+    #[test]
+    fn test_optim_walks_known_answer_p751() {
+        use crate::utils::{shake::ShakeRng, strategy::*};
+
+        let params = sike_p751_params(
+            Some(P751_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P751_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+        let iso = CurveIsogenies::init(params.clone());
+
+        let mut rng = ShakeRng::new(b"optimised walks");
+        let sk2 = SecretKey::random_with_rng(params.keyspace2 as usize, &mut rng);
+        let sk3 = SecretKey::random_with_rng(params.keyspace3 as usize, &mut rng);
+
+        // Fingerprints computed before the walks stopped cloning points
+        assert_eq!(
+            iso.isogen2_untyped(&sk2).unwrap().fingerprint_hex(),
+            "826e7400945780745867902cd8c190a2e2dafad462c9d283c7aad30e0efb4314"
+        );
+        assert_eq!(
+            iso.isogen3_untyped(&sk3).unwrap().fingerprint_hex(),
+            "4db68b31cd8bcd89b2e92c3de32791cc2abab8b285755b89e6a2f4b249e19507"
+        );
+    }
 }
@@ -28,7 +28,7 @@ impl<K: FiniteField + Clone> Point<K> {
 }
 
 impl<K: FiniteField + Clone> PartialEq<Self> for Point<K> {
-    /// Two points are equal if (z != 0 and x/z) match, or if z = 0 for both
+    /// Two points are equal if (z != 0 and x * z' = x' * z), or if z = 0 for both
     fn eq(&self, other: &Self) -> bool {
         let other_zero = other.z.is_zero();
         if self.z.is_zero() {
@@ -36,10 +36,10 @@ impl<K: FiniteField + Clone> PartialEq<Self> for Point<K> {
         } else if other_zero {
             false
         } else {
-            // Z / Z' are not zero and operation take place on finite field, div cannot panic
-            let ratio = self.x.div(&self.z).unwrap();
-            let other_ratio = &other.x.div(&other.z).unwrap();
-            ratio.equals(&other_ratio)
+            // Cross-multiplication avoids the two inversions of x/z = x'/z'
+            let lhs = self.x.mul(&other.z);
+            let rhs = other.x.mul(&self.z);
+            lhs.equals(&rhs)
         }
     }
 }
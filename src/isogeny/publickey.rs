@@ -1,20 +1,33 @@
 //! Public key material
 
-use crate::ff::FiniteField;
+use crate::{
+    error::SikeError,
+    ff::{self, FiniteField, QuadraticExtension},
+    isogeny::{Curve, PublicParameters},
+    utils::{conversion, shake},
+};
+use std::convert::TryInto;
 
 /// Public key
 ///
 /// The public key is a curve, but can be represented as a triple of points, of which only
-/// the x-coordinate is stored.
+/// the x-coordinate is stored. The points are the images of the basis of the other torsion,
+/// in the order (P, Q, Q - P).
+///
+/// There is no constructor from the curve alone: the coefficient A is determined by the
+/// three points (see `Curve::from_public_key`), but not the other way round. The images
+/// φ(P) and φ(Q) depend on the secret isogeny φ, and any basis of the torsion of E_A
+/// computed from A differs from them by an unknown change of basis. This is why compressed
+/// formats send, besides A, the coordinates of φ(P) and φ(Q) in a basis derived from A.
 #[derive(Clone)]
 pub struct PublicKey<K: FiniteField> {
-    /// First point
+    /// First point, x(P)
     pub x1: K,
 
-    /// Second point
+    /// Second point, x(Q)
     pub x2: K,
 
-    /// Third point
+    /// Third point, x(Q - P)
     pub x3: K,
 }
 
@@ -27,25 +40,361 @@ impl<K: FiniteField + std::fmt::Debug> std::fmt::Debug for PublicKey<K> {
 impl<K: FiniteField> PublicKey<K> {
     /// Converts the public key to a sequence of bytes (for each point)
     pub fn into_bytes(self) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
-        (
-            self.x1.into_bytes(),
-            self.x2.into_bytes(),
-            self.x3.into_bytes(),
-        )
+        self.to_bytes()
+    }
+
+    /// Converts the public key to a sequence of bytes (for each point), see `into_bytes`
+    pub fn to_bytes(&self) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        (self.x1.to_bytes(), self.x2.to_bytes(), self.x3.to_bytes())
+    }
+
+    /// Creates a public key from the x-coordinates of P, Q and Q - P
+    pub fn from_torsion_points(x_p: K, x_q: K, x_qmp: K) -> Self {
+        Self {
+            x1: x_p,
+            x2: x_q,
+            x3: x_qmp,
+        }
+    }
+
+    /// Creates a public key from affine x-coordinates computed elsewhere, as
+    /// `from_torsion_points`
+    ///
+    /// A public key always holds affine coordinates, i.e. (X : Z) with Z = 1. Points in
+    /// projective coordinates go through `ProjectivePublicKey::to_affine` instead, which
+    /// normalises them with a single inversion.
+    pub fn from_affine_coords(x1: K, x2: K, x3: K) -> Self {
+        Self::from_torsion_points(x1, x2, x3)
+    }
+
+    /// x-coordinate of P
+    pub fn x_p(&self) -> &K {
+        &self.x1
+    }
+
+    /// x-coordinate of Q
+    pub fn x_q(&self) -> &K {
+        &self.x2
+    }
+
+    /// x-coordinate of Q - P
+    pub fn x_qmp(&self) -> &K {
+        &self.x3
     }
 
     /// Creates a new public key for given three points (represented as bytes)
-    pub fn from_bytes(part1: &[u8], part2: &[u8], part3: &[u8]) -> Result<Self, String> {
+    ///
+    /// Coordinates that are not reduced are rejected with `NonCanonicalEncoding`, so that
+    /// each key (and each ciphertext `c0`) has a single encoding.
+    pub fn from_bytes(part1: &[u8], part2: &[u8], part3: &[u8]) -> Result<Self, SikeError> {
         Ok(Self {
-            x1: K::from_bytes(part1)?,
-            x2: K::from_bytes(part2)?,
-            x3: K::from_bytes(part3)?,
+            x1: K::from_bytes_strict(part1)?,
+            x2: K::from_bytes_strict(part2)?,
+            x3: K::from_bytes_strict(part3)?,
         })
     }
 }
 
+impl<K: FiniteField + Clone> PublicKey<K> {
+    /// Short fingerprint of the public key, suitable for display
+    ///
+    /// The fingerprint is the SHAKE-256 hash of the byte representation, truncated to 32 bytes
+    pub fn fingerprint(&self) -> [u8; 32] {
+        let (part1, part2, part3) = self.clone().into_bytes();
+        let input = conversion::concatenate(&[&part1, &part2, &part3]);
+
+        let mut fingerprint = [0; 32];
+        fingerprint.copy_from_slice(&shake::shake256(&input, 32));
+        fingerprint
+    }
+
+    /// Fingerprint of the public key as a hexadecimal string
+    pub fn fingerprint_hex(&self) -> String {
+        hex::encode(self.fingerprint())
+    }
+
+    /// The three points as (X : Z), with Z = 1, in the layout of `ProjectivePublicKey`
+    pub fn to_projective(&self) -> [(K, K); 3] {
+        [
+            (self.x1.clone(), K::one()),
+            (self.x2.clone(), K::one()),
+            (self.x3.clone(), K::one()),
+        ]
+    }
+
+    /// Checks that the three points define a curve, before any isogeny computation
+    ///
+    /// The coefficient A recovered from the points (see `Curve::from_public_key`) must exist
+    /// and give a nonsingular curve, A ≠ ±2. Fails with `InvalidPublicKey` otherwise. The
+    /// orders of the points are not checked.
+    pub fn validate(&self) -> Result<(), SikeError> {
+        let curve = Curve::from_public_key(self).map_err(|_| SikeError::InvalidPublicKey)?;
+
+        // y² = x³ + Ax² + x is singular iff A² = 4
+        let two_c = curve.c.add(&curve.c);
+        if curve.a.mul(&curve.a).equals(&two_c.mul(&two_c)) {
+            return Err(SikeError::InvalidPublicKey);
+        }
+
+        Ok(())
+    }
+
+    /// True if the curve of the public key is isomorphic to the starting curve of `params`
+    ///
+    /// Such a key carries no secret isogeny (e.g. the unmoved basis of `params`), and the
+    /// shared secrets derived from it are meaningless. Keys that do not define a curve are
+    /// not trivial, they are rejected by the isogeny computations. A zero secret scalar does
+    /// not produce a trivial key: the kernel P + [0]Q = P still has full order.
+    pub fn is_trivial(&self, params: &PublicParameters<K>) -> bool {
+        let basis = PublicKey::from_torsion_points(
+            params.xp2.clone(),
+            params.xq2.clone(),
+            params.xr2.clone(),
+        );
+        let j = |pk: &Self| Curve::from_public_key(pk).and_then(|curve| curve.j_invariant());
+
+        match (j(self), j(&basis)) {
+            (Ok(j), Ok(j_start)) => j.equals(&j_start),
+            _ => false,
+        }
+    }
+
+    /// True if the two public keys define the same curve up to isomorphism
+    ///
+    /// Compares the j-invariants of the curves recovered by `Curve::from_public_key`, fails
+    /// if either key does not define a curve. Unlike `==`, keys with different points (or a
+    /// different coefficient A) on isomorphic curves are equivalent.
+    pub fn same_curve(&self, other: &Self) -> Result<bool, SikeError> {
+        let j = Curve::from_public_key(self)?.j_invariant()?;
+        let j_other = Curve::from_public_key(other)?.j_invariant()?;
+
+        Ok(j.equals(&j_other))
+    }
+}
+
 impl<K: FiniteField> std::cmp::PartialEq for PublicKey<K> {
     fn eq(&self, other: &Self) -> bool {
         self.x1.equals(&other.x1) && self.x2.equals(&other.x2) && self.x3.equals(&other.x3)
     }
 }
+
+/// Public key of the 2-torsion side, output by `isogen2` and consumed by `isoex3`
+///
+/// The roles are: the side holding a 2-torsion secret key (`keyspace2`, the encapsulating
+/// side of the KEM) publishes a `PublicKey2` and computes the shared j-invariant from a
+/// `PublicKey3`; the side holding a 3-torsion secret key (`keyspace3`, the key owner of the
+/// KEM) publishes a `PublicKey3` and computes the shared j-invariant from a `PublicKey2`.
+/// Passing a key of the wrong side does not compile:
+///
+/// ```compile_fail
+/// use rust_sike::{sike_p434_params, CurveIsogenies, SecretKey};
+/// let params = sike_p434_params(None, None).unwrap();
+/// let iso = CurveIsogenies::init(params.clone());
+///
+/// let sk2 = SecretKey::get_random_secret_key(params.keyspace2 as usize).unwrap();
+/// let pk2 = iso.isogen2(&sk2).unwrap();
+///
+/// // `isoex2` expects the public key of the other side
+/// iso.isoex2(&sk2, &pk2).unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublicKey2<K: FiniteField>(PublicKey<K>);
+
+/// Public key of the 3-torsion side, output by `isogen3` and consumed by `isoex2`
+///
+/// See `PublicKey2` for the role mapping.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublicKey3<K: FiniteField>(PublicKey<K>);
+
+impl<K: FiniteField> PublicKey2<K> {
+    /// Tags a public key as coming from the 2-torsion side
+    ///
+    /// The tag is not checked: the points of a public key do not tell which torsion
+    /// they come from.
+    pub fn new(pk: PublicKey<K>) -> Self {
+        Self(pk)
+    }
+
+    /// The untagged public key
+    pub fn as_public_key(&self) -> &PublicKey<K> {
+        &self.0
+    }
+
+    /// Drops the tag
+    pub fn into_inner(self) -> PublicKey<K> {
+        self.0
+    }
+}
+
+impl<K: FiniteField> PublicKey3<K> {
+    /// Tags a public key as coming from the 3-torsion side
+    ///
+    /// The tag is not checked: the points of a public key do not tell which torsion
+    /// they come from.
+    pub fn new(pk: PublicKey<K>) -> Self {
+        Self(pk)
+    }
+
+    /// The untagged public key
+    pub fn as_public_key(&self) -> &PublicKey<K> {
+        &self.0
+    }
+
+    /// Drops the tag
+    pub fn into_inner(self) -> PublicKey<K> {
+        self.0
+    }
+}
+
+/// Public key with its curve recovered, for several exchanges against the same key
+///
+/// Holds the curve (A : 1) of `Curve::from_public_key`, and its forms (A + 2 : 4) and
+/// (A + 2 : A - 2) walked by `isoex2_prepared` and `isoex3_prepared`.
+pub struct PreparedPublicKey<K: FiniteField> {
+    pub(crate) pk: PublicKey<K>,
+    pub(crate) curve: Curve<K>,
+    pub(crate) curve_plus: Curve<K>,
+    pub(crate) curve_pm: Curve<K>,
+}
+
+impl<K: FiniteField + Clone> PreparedPublicKey<K> {
+    /// Recovers the curve of `pk`, fails with `InvalidPublicKey` as `Curve::from_public_key`
+    pub fn new(pk: PublicKey<K>) -> Result<Self, SikeError> {
+        let curve = Curve::from_public_key(&pk)?;
+
+        Ok(Self {
+            curve_plus: curve.curve_plus(),
+            curve_pm: curve.curve_plus_minus(),
+            curve,
+            pk,
+        })
+    }
+
+    /// The public key
+    pub fn public_key(&self) -> &PublicKey<K> {
+        &self.pk
+    }
+}
+
+impl<K: FiniteField + Clone> Clone for PreparedPublicKey<K> {
+    fn clone(&self) -> Self {
+        Self {
+            pk: self.pk.clone(),
+            curve: self.curve.clone(),
+            curve_plus: self.curve_plus.clone(),
+            curve_pm: self.curve_pm.clone(),
+        }
+    }
+}
+
+/// Public key with the points in projective coordinates (X : Z)
+///
+/// This is the output of the isogeny computation before normalisation, which costs
+/// inversions and can be skipped when the key is consumed in-process.
+#[derive(Clone)]
+pub struct ProjectivePublicKey<K: FiniteField> {
+    /// First point, P, as (X : Z)
+    pub p1: (K, K),
+
+    /// Second point, Q, as (X : Z)
+    pub p2: (K, K),
+
+    /// Third point, Q - P, as (X : Z)
+    pub p3: (K, K),
+}
+
+impl<K: FiniteField + Clone> ProjectivePublicKey<K> {
+    /// Normalises the points to their affine x-coordinates
+    ///
+    /// The three Z coordinates are inverted together, with a single field inversion.
+    pub fn to_affine(&self) -> Result<PublicKey<K>, SikeError> {
+        let z_invs = ff::batch_inv(&[self.p1.1.clone(), self.p2.1.clone(), self.p3.1.clone()])?;
+
+        Ok(PublicKey {
+            x1: self.p1.0.mul(&z_invs[0]),
+            x2: self.p2.0.mul(&z_invs[1]),
+            x3: self.p3.0.mul(&z_invs[2]),
+        })
+    }
+
+    /// Normalises the points as `to_affine`, with one field inversion per point
+    ///
+    /// Only kept as the baseline of the batched normalisation in the benches.
+    pub fn to_affine_unbatched(&self) -> Result<PublicKey<K>, SikeError> {
+        Ok(PublicKey {
+            x1: self.p1.0.div(&self.p1.1)?,
+            x2: self.p2.0.div(&self.p2.1)?,
+            x3: self.p3.0.div(&self.p3.1)?,
+        })
+    }
+}
+
+/// Public key stored in a byte array of statically known size `N`
+///
+/// The three points are encoded one after the other, and each coordinate of each point is
+/// left-padded to `N / 6` bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedPublicKey<const N: usize> {
+    bytes: [u8; N],
+}
+
+/// Fixed-size public key for SIKEp434 (6 × 55 bytes)
+pub type FixedPublicKeyP434 = FixedPublicKey<330>;
+
+/// Fixed-size public key for SIKEp503 (6 × 63 bytes)
+pub type FixedPublicKeyP503 = FixedPublicKey<378>;
+
+/// Fixed-size public key for SIKEp610 (6 × 77 bytes)
+pub type FixedPublicKeyP610 = FixedPublicKey<462>;
+
+/// Fixed-size public key for SIKEp751 (6 × 94 bytes)
+pub type FixedPublicKeyP751 = FixedPublicKey<564>;
+
+impl<const N: usize> FixedPublicKey<N> {
+    /// Size in bytes of the public key
+    pub const LEN: usize = N;
+
+    /// Size in bytes of each coordinate of each point
+    const COORD_LEN: usize = N / 6;
+
+    /// Builds a public key from its byte array
+    pub fn from_array(bytes: [u8; N]) -> Self {
+        Self { bytes }
+    }
+
+    /// Returns the byte array of the public key
+    pub fn to_array(&self) -> [u8; N] {
+        self.bytes
+    }
+
+    /// Converts a public key to its fixed-size representation
+    pub fn from_public_key<F: FiniteField + std::fmt::Debug>(
+        pk: PublicKey<QuadraticExtension<F>>,
+    ) -> Result<Self, SikeError> {
+        let part1 = pk.x1.into_bytes_fixed(Self::COORD_LEN)?;
+        let part2 = pk.x2.into_bytes_fixed(Self::COORD_LEN)?;
+        let part3 = pk.x3.into_bytes_fixed(Self::COORD_LEN)?;
+
+        let bytes = conversion::concatenate(&[&part1, &part2, &part3])
+            .try_into()
+            .map_err(|_| SikeError::InvalidEncoding("public key size mismatch"))?;
+
+        Ok(Self { bytes })
+    }
+
+    /// Converts the fixed-size representation back to a public key
+    pub fn to_public_key<F: FiniteField + std::fmt::Debug>(
+        &self,
+    ) -> Result<PublicKey<QuadraticExtension<F>>, SikeError> {
+        if N % 6 != 0 {
+            return Err(SikeError::InvalidEncoding("public key size mismatch"));
+        }
+
+        let n = N / 3;
+        Ok(PublicKey {
+            x1: QuadraticExtension::from_bytes_fixed(&self.bytes[..n])?,
+            x2: QuadraticExtension::from_bytes_fixed(&self.bytes[n..2 * n])?,
+            x3: QuadraticExtension::from_bytes_fixed(&self.bytes[2 * n..])?,
+        })
+    }
+}
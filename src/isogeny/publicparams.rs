@@ -1,6 +1,7 @@
 //! Public parameters
 
 use crate::constants::{cs_p434::*, cs_p503::*, cs_p610::*, cs_p751::*};
+use crate::error::SikeError;
 use crate::ff::{
     QuadraticExtension,
     {
@@ -10,6 +11,8 @@ use crate::ff::{
 };
 use crate::utils::{conversion::*, strategy};
 
+use std::convert::TryFrom;
+
 /// Public parameters
 #[derive(Clone)]
 pub struct PublicParameters<K> {
@@ -53,12 +56,198 @@ pub struct PublicParameters<K> {
     pub xr3: K,
 }
 
+/// Largest exponent e2 or e3 accepted, well above those of the SIKE parameter sets
+const MAX_EXPONENT: u64 = 1024;
+
+/// Field multiplications (squarings included) of one step of the three-point ladder
+const LADDER_STEP_MULS: u64 = 11;
+
+/// Field multiplications of a point tripling
+const TRIPLE_MULS: u64 = 12;
+
+/// Field multiplications of the evaluation of a 3-isogeny at a point
+const THREE_ISOGENY_EVAL_MULS: u64 = 6;
+
+/// Field multiplications of the computation of a 3-isogenous curve
+const THREE_ISOGENOUS_CURVE_MULS: u64 = 5;
+
+impl<K> PublicParameters<K> {
+    /// Check that the parameters are consistent
+    pub fn validate(&self) -> Result<(), SikeError> {
+        if self.secparam % 8 != 0 {
+            return Err(SikeError::InvalidSecparam);
+        }
+
+        // Before any power or walk length is derived from the exponents
+        checked_exponent(self.e2)?;
+        checked_exponent(self.e3)?;
+
+        // Secret keys are drawn in [0, 2^e2) and [0, 2^⌊log₂(3^e3)⌋), stored on whole bytes
+        let bits2 = self.e2;
+        let bits3 = u64::from(self.cofactor_2()?.significant_bits() - 1);
+        if self.keyspace2 != (bits2 + 7) / 8 || self.keyspace3 != (bits3 + 7) / 8 {
+            return Err(SikeError::InconsistentParameters);
+        }
+
+        // Only the lengths, the shapes are checked when walking (see `strategy::validate_shape`)
+        if let Some(strat) = &self.e2_strategy {
+            strategy::check_len(strat, (self.e2 / 2) as usize)?;
+        }
+        if let Some(strat) = &self.e3_strategy {
+            strategy::check_len(strat, self.e3 as usize)?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether the 2-torsion and the 3-torsion isogenies use the strategy-based walk, i.e.
+    /// whether `e2_strategy` and `e3_strategy` are set
+    ///
+    /// Without a strategy, the simple walk is used, which is several times slower.
+    pub fn uses_optimized_walks(&self) -> (bool, bool) {
+        (self.e2_strategy.is_some(), self.e3_strategy.is_some())
+    }
+
+    /// Cofactor of the 2^e2-torsion in the order (p + 1)² of the curves, per coordinate: 3^e3
+    ///
+    /// Fails with `InconsistentParameters` if e3 is above `MAX_EXPONENT`.
+    pub fn cofactor_2(&self) -> Result<rug::Integer, SikeError> {
+        Ok(rug::Integer::from(rug::Integer::u_pow_u(
+            3,
+            checked_exponent(self.e3)?,
+        )))
+    }
+
+    /// Cofactor of the 3^e3-torsion in the order (p + 1)² of the curves, per coordinate: 2^e2
+    ///
+    /// Fails with `InconsistentParameters` if e2 is above `MAX_EXPONENT`.
+    pub fn cofactor_3(&self) -> Result<rug::Integer, SikeError> {
+        Ok(rug::Integer::from(1) << checked_exponent(self.e2)?)
+    }
+
+    /// Size in bytes of a public key in the fixed-size encoding (see `FixedPublicKey`)
+    ///
+    /// Derived from the prime p = 2^e2 3^e3 - 1 alone, so that buffers can be sized before
+    /// any key exists. The variable-size encoding of `PublicKey::into_bytes` can be shorter or
+    /// longer. Fails with `InconsistentParameters` if e2 or e3 is above `MAX_EXPONENT`.
+    pub fn public_key_byte_len(&self) -> Result<usize, SikeError> {
+        fixed_public_key_len(self.e2, self.e3)
+    }
+
+    /// Estimated number of field multiplications of a keygen (`isogen3`), squarings included
+    ///
+    /// Counts the three-point ladder over the bits of `sk3`, and the walk: triplings and
+    /// evaluations of the kernel points as weighted by `strategy::cost` (or the simple walk
+    /// without a strategy), then the isogenous curve and the evaluation of the three basis
+    /// points at each step. Additions and inversions are ignored. This is meant to compare
+    /// parameter sets, not to predict timings. The count saturates at `u64::MAX`, which is
+    /// also the estimate for a strategy that does not describe a tree (keygen then fails).
+    pub fn estimated_keygen_muls(&self) -> u64 {
+        let ladder = self
+            .keyspace3
+            .saturating_mul(8)
+            .saturating_mul(LADDER_STEP_MULS);
+
+        let kernel = match &self.e3_strategy {
+            Some(strat) => {
+                strategy::cost(strat, TRIPLE_MULS, THREE_ISOGENY_EVAL_MULS).unwrap_or(u128::MAX)
+            }
+            None => {
+                // Step i triples the kernel point e3 - 1 - i times, then evaluates it
+                let e3 = u128::from(self.e3);
+                let steps = e3.saturating_sub(1);
+                (e3 * steps / 2)
+                    .saturating_mul(u128::from(TRIPLE_MULS))
+                    .saturating_add(steps * u128::from(THREE_ISOGENY_EVAL_MULS))
+            }
+        };
+        let kernel = u64::try_from(kernel).unwrap_or(u64::MAX);
+
+        let steps = self
+            .e3
+            .saturating_mul(THREE_ISOGENOUS_CURVE_MULS + 3 * THREE_ISOGENY_EVAL_MULS);
+
+        ladder.saturating_add(kernel).saturating_add(steps)
+    }
+}
+
+/// SIKE parameter sets
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SikeParameterSet {
+    /// SIKEp434
+    P434,
+    /// SIKEp503
+    P503,
+    /// SIKEp610
+    P610,
+    /// SIKEp751
+    P751,
+}
+
+impl SikeParameterSet {
+    /// All parameter sets
+    pub const ALL: [SikeParameterSet; 4] = [Self::P434, Self::P503, Self::P610, Self::P751];
+
+    /// Size in bytes of a public key in the fixed-size encoding (see `FixedPublicKey`)
+    pub fn public_key_len(self) -> usize {
+        let (e2, e3) = self.exponents();
+        // The exponents of the parameter sets are below `MAX_EXPONENT`
+        fixed_public_key_len(e2, e3).unwrap()
+    }
+
+    /// Exponents (e2, e3) of the prime p = 2^e2 3^e3 - 1
+    fn exponents(self) -> (u64, u64) {
+        let (e2, e3) = match self {
+            Self::P434 => (SIKE_P434_E2, SIKE_P434_E3),
+            Self::P503 => (SIKE_P503_E2, SIKE_P503_E3),
+            Self::P610 => (SIKE_P610_E2, SIKE_P610_E3),
+            Self::P751 => (SIKE_P751_E2, SIKE_P751_E3),
+        };
+
+        // Parsing constant values, tests ensure no panic
+        (str_to_u64(e2).unwrap(), str_to_u64(e3).unwrap())
+    }
+}
+
+/// Exponent e2 or e3 as a `u32`, rejected with `InconsistentParameters` above `MAX_EXPONENT`
+///
+/// The exponents are public fields, possibly read from an untrusted source: they are checked
+/// before computing powers of 2 or 3, or walking that many isogenies.
+pub(crate) fn checked_exponent(e: u64) -> Result<u32, SikeError> {
+    if e > MAX_EXPONENT {
+        return Err(SikeError::InconsistentParameters);
+    }
+
+    Ok(e as u32)
+}
+
+/// Size in bytes of a public key in the fixed-size encoding for the prime p = 2^e2 3^e3 - 1
+///
+/// Three elements of 𝔽ₚ(i), each coordinate on ⌈log₂(p) / 8⌉ bytes
+fn fixed_public_key_len(e2: u64, e3: u64) -> Result<usize, SikeError> {
+    let mut p = rug::Integer::from(rug::Integer::u_pow_u(3, checked_exponent(e3)?));
+    p <<= checked_exponent(e2)?;
+    p -= 1u32;
+
+    Ok(6 * ((p.significant_bits() as usize + 7) / 8))
+}
+
+/// Guess the parameter set of a public key from its length in the fixed-size encoding
+///
+/// Each parameter set has a distinct public key size, unknown sizes return `None`
+pub fn detect_parameter_set(pk_bytes_len: usize) -> Option<SikeParameterSet> {
+    SikeParameterSet::ALL
+        .iter()
+        .copied()
+        .find(|set| set.public_key_len() == pk_bytes_len)
+}
+
 /// Load params for SIKE_p434
 pub fn sike_p434_params(
     strat2tor: Option<strategy::Torsion2Strategy>,
     strat3tor: Option<strategy::Torsion3Strategy>,
-) -> Result<PublicParameters<QuadraticExtension<PrimeFieldP434>>, String> {
-    Ok(PublicParameters {
+) -> Result<PublicParameters<QuadraticExtension<PrimeFieldP434>>, SikeError> {
+    let params = PublicParameters {
         secparam: 128,
         keyspace2: str_to_u64(SIKE_P434_NKS2)?,
         keyspace3: str_to_u64(SIKE_P434_NKS3)?,
@@ -72,15 +261,18 @@ pub fn sike_p434_params(
         xp3: str_to_p434(SIKE_P434_XP30, SIKE_P434_XP31)?,
         xq3: str_to_p434(SIKE_P434_XQ30, SIKE_P434_XQ31)?,
         xr3: str_to_p434(SIKE_P434_XR30, SIKE_P434_XR31)?,
-    })
+    };
+    params.validate()?;
+
+    Ok(params)
 }
 
 /// Load params for SIKE_p503
 pub fn sike_p503_params(
     strat2tor: Option<strategy::Torsion2Strategy>,
     strat3tor: Option<strategy::Torsion3Strategy>,
-) -> Result<PublicParameters<QuadraticExtension<PrimeFieldP503>>, String> {
-    Ok(PublicParameters {
+) -> Result<PublicParameters<QuadraticExtension<PrimeFieldP503>>, SikeError> {
+    let params = PublicParameters {
         secparam: 192,
         keyspace2: str_to_u64(SIKE_P503_NKS2)?,
         keyspace3: str_to_u64(SIKE_P503_NKS3)?,
@@ -94,15 +286,18 @@ pub fn sike_p503_params(
         xp3: str_to_p503(SIKE_P503_XP30, SIKE_P503_XP31)?,
         xq3: str_to_p503(SIKE_P503_XQ30, SIKE_P503_XQ31)?,
         xr3: str_to_p503(SIKE_P503_XR30, SIKE_P503_XR31)?,
-    })
+    };
+    params.validate()?;
+
+    Ok(params)
 }
 
 /// Load params for SIKE_p610
 pub fn sike_p610_params(
     strat2tor: Option<strategy::Torsion2Strategy>,
     strat3tor: Option<strategy::Torsion3Strategy>,
-) -> Result<PublicParameters<QuadraticExtension<PrimeFieldP610>>, String> {
-    Ok(PublicParameters {
+) -> Result<PublicParameters<QuadraticExtension<PrimeFieldP610>>, SikeError> {
+    let params = PublicParameters {
         secparam: 192,
         keyspace2: str_to_u64(SIKE_P610_NKS2)?,
         keyspace3: str_to_u64(SIKE_P610_NKS3)?,
@@ -116,15 +311,18 @@ pub fn sike_p610_params(
         xp3: str_to_p610(SIKE_P610_XP30, SIKE_P610_XP31)?,
         xq3: str_to_p610(SIKE_P610_XQ30, SIKE_P610_XQ31)?,
         xr3: str_to_p610(SIKE_P610_XR30, SIKE_P610_XR31)?,
-    })
+    };
+    params.validate()?;
+
+    Ok(params)
 }
 
 /// Load params for SIKE_p751
 pub fn sike_p751_params(
     strat2tor: Option<strategy::Torsion2Strategy>,
     strat3tor: Option<strategy::Torsion3Strategy>,
-) -> Result<PublicParameters<QuadraticExtension<PrimeFieldP751>>, String> {
-    Ok(PublicParameters {
+) -> Result<PublicParameters<QuadraticExtension<PrimeFieldP751>>, SikeError> {
+    let params = PublicParameters {
         secparam: 256,
         keyspace2: str_to_u64(SIKE_P751_NKS2)?,
         keyspace3: str_to_u64(SIKE_P751_NKS3)?,
@@ -138,5 +336,8 @@ pub fn sike_p751_params(
         xp3: str_to_p751(SIKE_P751_XP30, SIKE_P751_XP31)?,
         xq3: str_to_p751(SIKE_P751_XQ30, SIKE_P751_XQ31)?,
         xr3: str_to_p751(SIKE_P751_XR30, SIKE_P751_XR31)?,
-    })
+    };
+    params.validate()?;
+
+    Ok(params)
 }
@@ -1,15 +1,114 @@
 //! Secret key
+//!
+//! Keys are zeroed on drop. With the `mlock` feature, each key is also stored on pages of its
+//! own, locked in memory (`mlock` on Unix, `VirtualLock` on Windows) so that it is not written
+//! to swap. Since no other allocation shares these pages, unlocking them when the key is
+//! dropped does not unlock anything else. This costs two pages per key. Locking is best
+//! effort: if the OS refuses it (e.g. `RLIMIT_MEMLOCK` is reached, or the process lacks the
+//! privilege), the key is used unlocked.
+use crate::error::SikeError;
 use bitvec::prelude::*;
+use rand_core::RngCore;
+use rug::{integer::Order, Integer};
+use zeroize::{Zeroize, Zeroizing};
+
+#[cfg(feature = "mlock")]
+use std::ops::{Deref, DerefMut};
+
+/// Buffer of a secret key
+#[cfg(not(feature = "mlock"))]
+type KeyBytes = Vec<u8>;
+
+/// Buffer of a secret key
+#[cfg(feature = "mlock")]
+type KeyBytes = LockedBytes;
+
+/// Zero-initialised buffer of `len` bytes for a secret key
+#[cfg(not(feature = "mlock"))]
+fn zeroed_key_bytes(len: usize) -> KeyBytes {
+    vec![0; len]
+}
+
+/// Zero-initialised buffer of `len` bytes for a secret key
+#[cfg(feature = "mlock")]
+fn zeroed_key_bytes(len: usize) -> KeyBytes {
+    LockedBytes::zeroed(len)
+}
+
+/// Bytes on whole pages of their own, locked in memory when the OS allows it
+///
+/// The pages are the ones fully inside an over-allocated `Vec`, so that locking and unlocking
+/// them never affects another allocation.
+#[cfg(feature = "mlock")]
+struct LockedBytes {
+    // Declared first so that the pages are unlocked before the buffer is freed
+    _lock: Option<region::LockGuard>,
+    buffer: Vec<u8>,
+    start: usize,
+    len: usize,
+}
+
+#[cfg(feature = "mlock")]
+impl LockedBytes {
+    fn zeroed(len: usize) -> Self {
+        let page = region::page::size();
+        let pages_len = ((len + page - 1) / page).max(1) * page;
+
+        // The buffer is never resized, so its first page boundary does not move
+        let buffer = vec![0; pages_len + page - 1];
+        let start = (page - buffer.as_ptr() as usize % page) % page;
+
+        Self {
+            _lock: region::lock(buffer[start..].as_ptr(), pages_len).ok(),
+            buffer,
+            start,
+            len,
+        }
+    }
+}
+
+#[cfg(feature = "mlock")]
+impl Deref for LockedBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buffer[self.start..self.start + self.len]
+    }
+}
+
+#[cfg(feature = "mlock")]
+impl DerefMut for LockedBytes {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.buffer[self.start..self.start + self.len]
+    }
+}
 
-#[derive(Clone, PartialEq)]
 /// Secret key
 pub struct SecretKey {
-    bytes: Vec<u8>,
+    bytes: KeyBytes,
+}
+
+impl Clone for SecretKey {
+    fn clone(&self) -> Self {
+        Self::from_bytes(&self.bytes)
+    }
+}
+
+impl PartialEq for SecretKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes[..] == other.bytes[..]
+    }
+}
+
+impl Drop for SecretKey {
+    fn drop(&mut self) {
+        self.bytes[..].zeroize();
+    }
 }
 
 impl std::fmt::Debug for SecretKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self.bytes)
+        write!(f, "{:?}", &self.bytes[..])
     }
 }
 
@@ -23,17 +122,39 @@ impl SecretKey {
     /// let key = SecretKey::get_random_secret_key(64);
     /// println!("{:?}", key);
     /// ```
-    pub fn get_random_secret_key(size: usize) -> Result<Self, String> {
-        let mut bytes = vec![0; size];
-        if let Err(_e) = getrandom::getrandom(&mut bytes) {
-            return Err(String::from("RNG Error"));
-        };
-        Ok(Self::from_bytes(&bytes))
+    pub fn get_random_secret_key(size: usize) -> Result<Self, SikeError> {
+        // Lock the buffer before it holds the key
+        let mut key = Self::zeroed(size);
+        getrandom::getrandom(&mut key.bytes)?;
+        Ok(key)
+    }
+
+    /// Get a secret key of given `size` in bytes drawn from `rng`
+    pub fn random_with_rng<R: RngCore>(size: usize, rng: &mut R) -> Self {
+        let mut key = Self::zeroed(size);
+        rng.fill_bytes(&mut key.bytes);
+        key
+    }
+
+    /// Zero key of `len` bytes, locked in memory if the `mlock` feature is enabled
+    fn zeroed(len: usize) -> Self {
+        Self {
+            bytes: zeroed_key_bytes(len),
+        }
+    }
+
+    /// Moves `bytes` into a new key, wiping the vector
+    fn from_vec(mut bytes: Vec<u8>) -> Self {
+        let key = Self::from_bytes(&bytes);
+        bytes.zeroize();
+        key
     }
 
     /// Converts the secret key into a sequence of bits
     ///
-    /// Note: The format is big endian
+    /// Note: The format is big endian, the bytes are the little-endian encoding of the scalar
+    /// (see `to_integer`). This is a copy of the key that is neither locked nor zeroed on
+    /// drop, the ladder reads `bits_lsb_first` instead.
     pub fn to_bits(&self) -> BitVec<Msb0, u8> {
         // We reverse the order of the bytes
         // such that bits are properly ordered
@@ -42,15 +163,186 @@ impl SecretKey {
         BitVec::<Msb0, u8>::from_vec(bytes)
     }
 
-    /// Converts the secret key to bytes
-    pub fn to_bytes(&self) -> Vec<u8> {
-        self.bytes.clone()
+    /// Iterates over the bits of the secret key from the least significant one, i.e. `to_bits`
+    /// in reverse, without allocating them
+    pub fn bits_lsb_first(&self) -> impl Iterator<Item = bool> + '_ {
+        self.bytes
+            .iter()
+            .flat_map(|&byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+    }
+
+    /// Converts the secret key to bytes, zeroed on drop (but not locked in memory)
+    pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        Zeroizing::new(self.bytes.to_vec())
+    }
+
+    /// Converts the secret key to exactly ⌈keyspace_bits / 8⌉ bytes, dropping or adding zero
+    /// high bytes
+    ///
+    /// The key spaces `keyspace2` and `keyspace3` are already this minimal length, so keys
+    /// drawn by `get_random_secret_key` are unchanged. Fails with `InvalidSecretKeyLength` if
+    /// a dropped byte is not zero. Bits above `keyspace_bits` in the last byte are kept, see
+    /// `from_bytes_clamped` to clear them.
+    pub fn minimal_bytes(&self, keyspace_bits: usize) -> Result<Zeroizing<Vec<u8>>, SikeError> {
+        let len = (keyspace_bits + 7) / 8;
+        if self.bytes.iter().skip(len).any(|&b| b != 0) {
+            return Err(SikeError::InvalidSecretKeyLength {
+                len: self.bytes.len(),
+                keyspace: len,
+            });
+        }
+
+        let mut bytes = self.to_bytes();
+        bytes.resize(len, 0);
+        Ok(bytes)
+    }
+
+    /// Checks that the key fits in a key space of `keyspace` bytes
+    ///
+    /// Shorter keys are accepted (they are zero-extended by the ladder), longer keys
+    /// would silently run extra ladder steps and are rejected.
+    pub fn check_length(&self, keyspace: u64) -> Result<(), SikeError> {
+        if self.bytes.len() as u64 > keyspace {
+            return Err(SikeError::InvalidSecretKeyLength {
+                len: self.bytes.len(),
+                keyspace: keyspace as usize,
+            });
+        }
+        Ok(())
     }
 
     /// Build a secret key from bytes
     pub fn from_bytes(bytes: &[u8]) -> Self {
-        Self {
-            bytes: bytes.to_vec(),
+        let mut key = Self::zeroed(bytes.len());
+        key.bytes.copy_from_slice(bytes);
+        key
+    }
+
+    /// Build a secret key from little-endian bytes, keeping only the low `keyspace_bits` bits
+    ///
+    /// Bytes beyond the key space are dropped, and the bits above `keyspace_bits` in the last
+    /// byte are cleared, so that the scalar lies in [0, 2^keyspace_bits) (`e2` for sk2,
+    /// ⌊log₂(3^e3)⌋ for sk3). `from_bytes` keeps them, and the ladder then runs on a
+    /// different scalar.
+    pub fn from_bytes_clamped(bytes: &[u8], keyspace_bits: u64) -> Self {
+        let len = bytes.len().min(((keyspace_bits + 7) / 8) as usize);
+        let mut key = Self::from_bytes(&bytes[..len]);
+
+        let extra_bits = 8 * len as u64 - keyspace_bits.min(8 * len as u64);
+        if let Some(last) = key.bytes.last_mut() {
+            *last &= 0xff >> extra_bits;
         }
+        key
+    }
+
+    /// Reads the secret key as a little-endian integer, the one whose big-endian bits are
+    /// `to_bits`
+    ///
+    /// **This exposes the secret:** the integer is not locked in memory nor zeroed on drop,
+    /// and operations on it are not constant-time.
+    pub fn to_integer(&self) -> Integer {
+        Integer::from_digits(&self.bytes, Order::Lsf)
+    }
+
+    /// Builds a secret key of `byte_len` bytes from a non-negative integer, the inverse of
+    /// `to_integer`
+    ///
+    /// Fails with `InvalidSecretKeyLength` if `n` is negative or does not fit. Same caveats
+    /// as `to_integer`.
+    pub fn from_integer(n: &Integer, byte_len: usize) -> Result<Self, SikeError> {
+        let mut bytes = n.to_digits::<u8>(Order::Lsf);
+        if n.cmp0() == std::cmp::Ordering::Less || bytes.len() > byte_len {
+            return Err(SikeError::InvalidSecretKeyLength {
+                len: bytes.len(),
+                keyspace: byte_len,
+            });
+        }
+        bytes.resize(byte_len, 0);
+
+        Ok(Self::from_vec(bytes))
+    }
+
+    /// Adds two secret keys, read as little-endian integers, modulo `modulus`
+    ///
+    /// The result has the size of the longest key (or more if needed by the modulus)
+    pub fn add_mod(&self, other: &Self, modulus: &Integer) -> Self {
+        let a = Integer::from_digits(&self.bytes, Order::Lsf);
+        let b = Integer::from_digits(&other.bytes, Order::Lsf);
+        let sum = (a + b) % modulus;
+
+        let mut bytes = sum.to_digits::<u8>(Order::Lsf);
+        let len = self.bytes.len().max(other.bytes.len()).max(bytes.len());
+        bytes.resize(len, 0);
+
+        Self::from_vec(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_key_buffer() {
+        let key = SecretKey::get_random_secret_key(28).unwrap();
+        let copy = key.clone();
+        assert_eq!(key, copy);
+        assert_eq!(key.to_bytes().len(), 28);
+
+        #[cfg(feature = "mlock")]
+        {
+            // Each key has pages of its own
+            let page = region::page::size();
+            assert_eq!(
+                key.bytes.buffer[key.bytes.start..].as_ptr() as usize % page,
+                0
+            );
+            assert_ne!(key.bytes.as_ptr(), copy.bytes.as_ptr());
+            assert_eq!(key.bytes._lock.is_some(), copy.bytes._lock.is_some());
+        }
+
+        let modulus = Integer::from(1) << 224;
+        let sum = key.add_mod(&SecretKey::from_bytes(&[1]), &modulus);
+        assert_ne!(sum, key);
+
+        drop(key);
+        assert_eq!(copy.to_bits().len(), 28 * 8);
+    }
+
+    #[test]
+    fn test_secret_key_integer() {
+        let key = SecretKey::get_random_secret_key(28).unwrap();
+        let n = key.to_integer();
+        assert_eq!(SecretKey::from_integer(&n, 28).unwrap(), key);
+
+        // Bit i of `to_bits` is the bit of weight 2^(len - 1 - i)
+        let bits = key.to_bits();
+        for (i, bit) in bits.iter().enumerate() {
+            assert_eq!(*bit, n.get_bit((bits.len() - 1 - i) as u32));
+        }
+        assert!(key.bits_lsb_first().eq(bits.iter().rev().copied()));
+
+        let one = SecretKey::from_integer(&Integer::from(1), 2).unwrap();
+        assert_eq!(*one.to_bytes(), vec![1, 0]);
+
+        assert!(SecretKey::from_integer(&Integer::from(1 << 16), 2).is_err());
+        assert!(SecretKey::from_integer(&Integer::from(-1), 2).is_err());
+    }
+
+    #[test]
+    fn test_secret_key_clamped() {
+        let key = SecretKey::from_bytes_clamped(&[0xff; 28], 217);
+        assert_eq!(key.to_integer(), (Integer::from(1) << 217) - 1);
+
+        // Extra bytes are dropped, shorter keys and whole bytes are kept
+        assert_eq!(SecretKey::from_bytes_clamped(&[0xff; 30], 217), key);
+        assert_eq!(
+            SecretKey::from_bytes_clamped(&[0xff; 3], 217).to_bytes()[..],
+            [0xff; 3]
+        );
+        assert_eq!(
+            SecretKey::from_bytes_clamped(&[0xff; 27], 216).to_bytes()[..],
+            [0xff; 27]
+        );
     }
 }
@@ -0,0 +1,177 @@
+//! Step-by-step isogeny walks
+
+use crate::{
+    ff::FiniteField,
+    isogeny::{point::Point, Curve, CurveIsogenies, DoublingContext, ThreePoints},
+};
+use std::fmt::Debug;
+
+/// Walk along a 2^k isogeny, one step at a time
+///
+/// Each step is a 4-isogeny, preceded by a single 2-isogeny when k is odd. The iterator
+/// yields the curve reached, as (A + 2C : 4C), and the image of the kernel generator.
+pub struct TwoIsogenyWalk<K: FiniteField + Clone> {
+    curve: Curve<K>,
+
+    /// Doubling constants of `curve`, computed on demand
+    ctx: Option<DoublingContext<K>>,
+    kernel: Point<K>,
+    k: u64,
+    opt: Option<ThreePoints<K>>,
+}
+
+impl<K: FiniteField + Clone + Debug> TwoIsogenyWalk<K> {
+    /// Start a walk with kernel S of order 2^k on a curve given as (A + 2C : 4C)
+    pub fn new(kernel: Point<K>, k: u64, curve: &Curve<K>) -> Self {
+        Self::with_points(kernel, k, None, curve)
+    }
+
+    /// Start a walk that also carries three points along
+    pub(crate) fn with_points(
+        kernel: Point<K>,
+        k: u64,
+        opt: Option<ThreePoints<K>>,
+        curve: &Curve<K>,
+    ) -> Self {
+        Self {
+            curve: curve.clone(),
+            ctx: None,
+            kernel,
+            k,
+            opt,
+        }
+    }
+
+    /// Current curve and images of the carried points
+    pub(crate) fn into_parts(self) -> (Curve<K>, Option<ThreePoints<K>>) {
+        (self.curve, self.opt)
+    }
+
+    /// Perform one step of the walk, returns false once the walk is over
+    pub(crate) fn advance(&mut self) -> bool {
+        if self.k == 0 {
+            return false;
+        }
+
+        let curve = &self.curve;
+        let ctx = self.ctx.get_or_insert_with(|| DoublingContext::new(curve));
+
+        if self.k % 2 == 1 {
+            self.k -= 1;
+            let t = CurveIsogenies::ndouble_with_ctx(&self.kernel, self.k, ctx);
+
+            self.curve = CurveIsogenies::two_isogenous_curve(&t);
+            self.ctx = None;
+            self.kernel = CurveIsogenies::two_isogeny_eval(&t, &self.kernel);
+            self.opt = self.opt.take().map(|(p1, p2, p3)| {
+                (
+                    CurveIsogenies::two_isogeny_eval(&t, &p1),
+                    CurveIsogenies::two_isogeny_eval(&t, &p2),
+                    CurveIsogenies::two_isogeny_eval(&t, &p3),
+                )
+            });
+        } else {
+            self.k -= 2;
+            let t = CurveIsogenies::ndouble_with_ctx(&self.kernel, self.k, ctx);
+
+            let (curve, k1, k2, k3) = CurveIsogenies::four_isogenous_curve(&t);
+            self.curve = curve;
+            self.ctx = None;
+            self.kernel = CurveIsogenies::four_isogeny_eval(&k1, &k2, &k3, &self.kernel);
+            self.opt = self.opt.take().map(|(p1, p2, p3)| {
+                (
+                    CurveIsogenies::four_isogeny_eval(&k1, &k2, &k3, &p1),
+                    CurveIsogenies::four_isogeny_eval(&k1, &k2, &k3, &p2),
+                    CurveIsogenies::four_isogeny_eval(&k1, &k2, &k3, &p3),
+                )
+            });
+        }
+
+        true
+    }
+}
+
+impl<K: FiniteField + Clone + Debug> Iterator for TwoIsogenyWalk<K> {
+    type Item = (Curve<K>, Point<K>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.advance() {
+            Some((self.curve.clone(), self.kernel.clone()))
+        } else {
+            None
+        }
+    }
+}
+
+/// Walk along a 3^e isogeny, one 3-isogeny at a time
+///
+/// The iterator yields the curve reached, as (A + 2C : A - 2C), and the image of the
+/// kernel generator.
+pub struct ThreeIsogenyWalk<K: FiniteField + Clone> {
+    curve: Curve<K>,
+    kernel: Point<K>,
+    e: u64,
+    opt: Option<ThreePoints<K>>,
+}
+
+impl<K: FiniteField + Clone + Debug> ThreeIsogenyWalk<K> {
+    /// Start a walk with kernel S of order 3^e on a curve given as (A + 2C : A - 2C)
+    pub fn new(kernel: Point<K>, e: u64, curve: &Curve<K>) -> Self {
+        Self::with_points(kernel, e, None, curve)
+    }
+
+    /// Start a walk that also carries three points along
+    pub(crate) fn with_points(
+        kernel: Point<K>,
+        e: u64,
+        opt: Option<ThreePoints<K>>,
+        curve: &Curve<K>,
+    ) -> Self {
+        Self {
+            curve: curve.clone(),
+            kernel,
+            e,
+            opt,
+        }
+    }
+
+    /// Current curve and images of the carried points
+    pub(crate) fn into_parts(self) -> (Curve<K>, Option<ThreePoints<K>>) {
+        (self.curve, self.opt)
+    }
+
+    /// Perform one step of the walk, returns false once the walk is over
+    pub(crate) fn advance(&mut self) -> bool {
+        if self.e == 0 {
+            return false;
+        }
+
+        self.e -= 1;
+        let t = CurveIsogenies::ntriple(&self.kernel, self.e, &self.curve);
+
+        let (curve, k1, k2) = CurveIsogenies::three_isogenous_curve(&t);
+        self.curve = curve;
+        self.kernel = CurveIsogenies::three_isogeny_eval(&self.kernel, &k1, &k2);
+        self.opt = self.opt.take().map(|(p1, p2, p3)| {
+            (
+                CurveIsogenies::three_isogeny_eval(&p1, &k1, &k2),
+                CurveIsogenies::three_isogeny_eval(&p2, &k1, &k2),
+                CurveIsogenies::three_isogeny_eval(&p3, &k1, &k2),
+            )
+        });
+
+        true
+    }
+}
+
+impl<K: FiniteField + Clone + Debug> Iterator for ThreeIsogenyWalk<K> {
+    type Item = (Curve<K>, Point<K>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.advance() {
+            Some((self.curve.clone(), self.kernel.clone()))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Byte-oriented KEM interface, for dispatching over parameter sets at runtime
+//!
+//! Public keys use the fixed-size encoding of `FixedPublicKey`, and ciphertexts are the
+//! fixed-size encoding of `c0` followed by `c1`.
+
+use super::KEM;
+use crate::{
+    error::SikeError,
+    ff::{PrimeFieldP434, PrimeFieldP503, PrimeFieldP610, PrimeFieldP751, QuadraticExtension},
+    isogeny::{
+        sike_p434_params, sike_p503_params, sike_p610_params, sike_p751_params, FixedPublicKeyP434,
+        FixedPublicKeyP503, FixedPublicKeyP610, FixedPublicKeyP751, PublicKey, SecretKey,
+        SikeParameterSet,
+    },
+    utils::strategy::*,
+};
+
+use std::convert::TryInto;
+
+/// Rejection secret, secret key and public key, encoded as bytes
+type KeyPairBytes = (Vec<u8>, Vec<u8>, Vec<u8>);
+
+/// Object-safe KEM operating on byte strings
+pub trait DynKem {
+    /// Parameter set of the KEM
+    fn parameter_set(&self) -> SikeParameterSet;
+
+    /// Generate a rejection secret `s`, a secret key and a public key
+    fn keygen(&self) -> Result<KeyPairBytes, SikeError>;
+
+    /// Encapsulate a shared secret for `pk`, returns the ciphertext and the shared secret
+    fn encaps(&self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>), SikeError>;
+
+    /// Decapsulate the shared secret of `ct`
+    fn decaps(&self, s: &[u8], sk: &[u8], pk: &[u8], ct: &[u8]) -> Result<Vec<u8>, SikeError>;
+}
+
+/// Build a KEM for `set`, using the reference tree-traversal strategies
+pub fn dyn_kem(set: SikeParameterSet) -> Result<Box<dyn DynKem>, SikeError> {
+    Ok(match set {
+        SikeParameterSet::P434 => Box::new(KEM::setup(sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )?)?),
+        SikeParameterSet::P503 => Box::new(KEM::setup(sike_p503_params(
+            Some(P503_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P503_THREE_TORSION_STRATEGY.to_vec()),
+        )?)?),
+        SikeParameterSet::P610 => Box::new(KEM::setup(sike_p610_params(
+            Some(P610_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P610_THREE_TORSION_STRATEGY.to_vec()),
+        )?)?),
+        SikeParameterSet::P751 => Box::new(KEM::setup(sike_p751_params(
+            Some(P751_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P751_THREE_TORSION_STRATEGY.to_vec()),
+        )?)?),
+    })
+}
+
+/// Run one keygen/encaps/decaps round trip for each parameter set, as a health check
+///
+/// Fails with `SmokeTestFailed` on the first set whose round trip errors or recovers a
+/// different shared secret.
+pub fn smoke_test() -> Result<(), SikeError> {
+    for &set in SikeParameterSet::ALL.iter() {
+        let round_trip = || -> Result<bool, SikeError> {
+            let kem = dyn_kem(set)?;
+            let (s, sk, pk) = kem.keygen()?;
+            let (ct, k) = kem.encaps(&pk)?;
+            Ok(kem.decaps(&s, &sk, &pk, &ct)? == k)
+        };
+
+        match round_trip() {
+            Ok(true) => {}
+            Ok(false) => return Err(SikeError::SmokeTestFailed { set, source: None }),
+            Err(e) => {
+                return Err(SikeError::SmokeTestFailed {
+                    set,
+                    source: Some(Box::new(e)),
+                })
+            }
+        }
+    }
+
+    Ok(())
+}
+
+macro_rules! impl_dyn_kem {
+    ($field:ty, $fixed:ty, $set:expr) => {
+        impl KEM<QuadraticExtension<$field>> {
+            fn encode_public_key(
+                pk: PublicKey<QuadraticExtension<$field>>,
+            ) -> Result<Vec<u8>, SikeError> {
+                Ok(<$fixed>::from_public_key(pk)?.to_array().to_vec())
+            }
+
+            fn decode_public_key(
+                bytes: &[u8],
+            ) -> Result<PublicKey<QuadraticExtension<$field>>, SikeError> {
+                let array = bytes
+                    .try_into()
+                    .map_err(|_| SikeError::InvalidEncoding("public key size mismatch"))?;
+                <$fixed>::from_array(array).to_public_key()
+            }
+        }
+
+        impl DynKem for KEM<QuadraticExtension<$field>> {
+            fn parameter_set(&self) -> SikeParameterSet {
+                $set
+            }
+
+            fn keygen(&self) -> Result<KeyPairBytes, SikeError> {
+                let (s, sk, pk) = KEM::keygen(self)?;
+                Ok((s, sk.to_bytes().to_vec(), Self::encode_public_key(pk)?))
+            }
+
+            fn encaps(&self, pk: &[u8]) -> Result<(Vec<u8>, Vec<u8>), SikeError> {
+                let pk = Self::decode_public_key(pk)?;
+                let (c, k) = KEM::encaps(self, &pk)?;
+
+                let mut ct = vec![0; self.ciphertext_byte_len()];
+                self.encode_ciphertext(c, &mut ct)?;
+
+                Ok((ct, k))
+            }
+
+            fn decaps(
+                &self,
+                s: &[u8],
+                sk: &[u8],
+                pk: &[u8],
+                ct: &[u8],
+            ) -> Result<Vec<u8>, SikeError> {
+                let c = self.decode_ciphertext(ct)?;
+                let pk = Self::decode_public_key(pk)?;
+                KEM::decaps(self, s, &SecretKey::from_bytes(sk), &pk, c)
+            }
+        }
+    };
+}
+
+impl_dyn_kem!(PrimeFieldP434, FixedPublicKeyP434, SikeParameterSet::P434);
+impl_dyn_kem!(PrimeFieldP503, FixedPublicKeyP503, SikeParameterSet::P503);
+impl_dyn_kem!(PrimeFieldP610, FixedPublicKeyP610, SikeParameterSet::P610);
+impl_dyn_kem!(PrimeFieldP751, FixedPublicKeyP751, SikeParameterSet::P751);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dyn_kem_all_sets() {
+        let kems = SikeParameterSet::ALL
+            .iter()
+            .map(|&set| dyn_kem(set).unwrap())
+            .collect::<Vec<Box<dyn DynKem>>>();
+
+        for (kem, set) in kems.iter().zip(SikeParameterSet::ALL.iter()) {
+            assert_eq!(kem.parameter_set(), *set);
+
+            let (s, sk, pk) = kem.keygen().unwrap();
+            assert_eq!(pk.len(), set.public_key_len());
+
+            let (ct, k) = kem.encaps(&pk).unwrap();
+            let k_recovered = kem.decaps(&s, &sk, &pk, &ct).unwrap();
+            assert_eq!(k, k_recovered);
+
+            // Keys of another parameter set are rejected
+            assert!(kem.encaps(&pk[1..]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_smoke_test() {
+        assert!(smoke_test().is_ok());
+    }
+}
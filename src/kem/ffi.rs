@@ -0,0 +1,270 @@
+//! Encapsulation and decapsulation on caller-provided buffers, as a base for a C interface
+//!
+//! Public keys and `c0` use the fixed-size encoding of `FixedPublicKey`, and ciphertexts are
+//! `c0` followed by `c1`. Buffers must have exactly the lengths given by
+//! `KEM::public_key_byte_len`, `KEM::ciphertext_byte_len` and
+//! `KEM::shared_secret_byte_len`, otherwise `InvalidEncoding` is returned before anything
+//! is written.
+
+use super::{KemSizes, KEM};
+use crate::{
+    error::SikeError,
+    ff::{FiniteField, QuadraticExtension},
+    isogeny::{PublicKey, SecretKey},
+    pke::Ciphertext,
+    utils::hash::SikeHash,
+};
+
+use std::fmt::Debug;
+
+impl<F: FiniteField + Clone + Debug, H: SikeHash> KEM<QuadraticExtension<F>, H> {
+    /// Size in bytes of a public key in the fixed-size encoding, see
+    /// `PublicParameters::public_key_byte_len`
+    pub fn public_key_byte_len(&self) -> usize {
+        self.pk_len
+    }
+
+    /// Size in bytes of a ciphertext, `c0` in the fixed-size encoding followed by `c1`
+    pub fn ciphertext_byte_len(&self) -> usize {
+        self.pk_len + self.shared_secret_byte_len()
+    }
+
+    /// Size in bytes of a shared secret (and of `c1`)
+    pub fn shared_secret_byte_len(&self) -> usize {
+        self.n / 8
+    }
+
+    /// All the sizes above, with the secret key as `s` followed by `sk3` (`SecretKey::to_bytes`)
+    pub fn sizes(&self) -> KemSizes {
+        KemSizes {
+            public_key: self.pk_len,
+            secret_key: self.shared_secret_byte_len() + self.params.keyspace3 as usize,
+            ciphertext: self.ciphertext_byte_len(),
+            shared_secret: self.shared_secret_byte_len(),
+        }
+    }
+
+    /// Encapsulate a shared secret for the public key `pk`, writing the ciphertext to
+    /// `out_ct` and the shared secret to `out_ss`
+    pub fn encaps_into(
+        &self,
+        pk: &[u8],
+        out_ct: &mut [u8],
+        out_ss: &mut [u8],
+    ) -> Result<(), SikeError> {
+        if out_ct.len() != self.ciphertext_byte_len() {
+            return Err(SikeError::InvalidEncoding(
+                "ciphertext buffer size mismatch",
+            ));
+        }
+        if out_ss.len() != self.shared_secret_byte_len() {
+            return Err(SikeError::InvalidEncoding(
+                "shared secret buffer size mismatch",
+            ));
+        }
+
+        let pk = self.decode_fixed(pk)?;
+        let (c, k) = self.encaps(&pk)?;
+
+        self.encode_ciphertext(c, out_ct)?;
+        out_ss.copy_from_slice(&k);
+
+        Ok(())
+    }
+
+    /// Decapsulate the shared secret of `ct` into `out_ss`, with the rejection value `s`,
+    /// the secret key `sk` (as in `SecretKey::to_bytes`) and the public key `pk`
+    pub fn decaps_into(
+        &self,
+        s: &[u8],
+        sk: &[u8],
+        pk: &[u8],
+        ct: &[u8],
+        out_ss: &mut [u8],
+    ) -> Result<(), SikeError> {
+        if out_ss.len() != self.shared_secret_byte_len() {
+            return Err(SikeError::InvalidEncoding(
+                "shared secret buffer size mismatch",
+            ));
+        }
+
+        let c = self.decode_ciphertext(ct)?;
+        let pk = self.decode_fixed(pk)?;
+        let k = self.decaps(s, &SecretKey::from_bytes(sk), &pk, c)?;
+        out_ss.copy_from_slice(&k);
+
+        Ok(())
+    }
+
+    /// Writes `c` into `out`, of `ciphertext_byte_len` bytes: `c0` in the fixed-size
+    /// encoding followed by `c1`
+    pub(super) fn encode_ciphertext(&self, c: Ciphertext, out: &mut [u8]) -> Result<(), SikeError> {
+        let (c0, c1) = out.split_at_mut(self.pk_len);
+        let c0_key = PublicKey::from_bytes(&c.bytes00, &c.bytes01, &c.bytes02)?;
+        self.encode_fixed(c0_key, c0)?;
+        c1.copy_from_slice(&c.bytes1);
+
+        Ok(())
+    }
+
+    /// Reads a ciphertext written by `encode_ciphertext`
+    pub(super) fn decode_ciphertext(&self, ct: &[u8]) -> Result<Ciphertext, SikeError> {
+        if ct.len() != self.ciphertext_byte_len() {
+            return Err(SikeError::InvalidEncoding("ciphertext size mismatch"));
+        }
+        let (c0, c1) = ct.split_at(self.pk_len);
+
+        // `decaps` only accepts the canonical variable-size encoding of c0
+        let (bytes00, bytes01, bytes02) = self.decode_fixed(c0)?.into_bytes();
+        Ok(Ciphertext {
+            bytes00,
+            bytes01,
+            bytes02,
+            bytes1: c1.to_vec(),
+        })
+    }
+
+    /// Writes `pk` in the fixed-size encoding into `out`, of `public_key_byte_len` bytes
+    fn encode_fixed(
+        &self,
+        pk: PublicKey<QuadraticExtension<F>>,
+        out: &mut [u8],
+    ) -> Result<(), SikeError> {
+        let coord_len = self.pk_len / 6;
+
+        for (x, chunk) in [pk.x1, pk.x2, pk.x3]
+            .iter()
+            .zip(out.chunks_exact_mut(2 * coord_len))
+        {
+            chunk.copy_from_slice(&x.clone().into_bytes_fixed(coord_len)?);
+        }
+
+        Ok(())
+    }
+
+    /// Reads a public key in the fixed-size encoding
+    fn decode_fixed(&self, bytes: &[u8]) -> Result<PublicKey<QuadraticExtension<F>>, SikeError> {
+        let len = self.pk_len;
+        if bytes.len() != len {
+            return Err(SikeError::InvalidEncoding("public key size mismatch"));
+        }
+
+        let n = len / 3;
+        Ok(PublicKey {
+            x1: QuadraticExtension::from_bytes_fixed(&bytes[..n])?,
+            x2: QuadraticExtension::from_bytes_fixed(&bytes[n..2 * n])?,
+            x3: QuadraticExtension::from_bytes_fixed(&bytes[2 * n..])?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ff::PrimeFieldP434,
+        isogeny::{
+            sike_p434_params, sike_p503_params, sike_p610_params, sike_p751_params,
+            FixedPublicKeyP434,
+        },
+        utils::strategy::*,
+    };
+
+    #[test]
+    fn test_kem_sizes() {
+        macro_rules! check_sizes {
+            ($params:expr) => {{
+                let kem = KEM::setup($params.unwrap()).unwrap();
+                let sizes = kem.sizes();
+
+                let (s, sk3, pk3) = kem.keygen().unwrap();
+                let mut pk_bytes = vec![0; sizes.public_key];
+                kem.encode_fixed(pk3.clone(), &mut pk_bytes).unwrap();
+                assert_eq!(kem.decode_fixed(&pk_bytes).unwrap(), pk3);
+                assert_eq!(s.len() + sk3.to_bytes().len(), sizes.secret_key);
+
+                // The buffers are only accepted at their exact sizes
+                let mut ct = vec![0; sizes.ciphertext];
+                let mut ss = vec![0; sizes.shared_secret];
+                kem.encaps_into(&pk_bytes, &mut ct, &mut ss).unwrap();
+
+                let mut k = vec![0; sizes.shared_secret];
+                kem.decaps_into(&s, &sk3.to_bytes(), &pk_bytes, &ct, &mut k)
+                    .unwrap();
+                assert_eq!(k, ss);
+            }};
+        }
+
+        check_sizes!(sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        ));
+        check_sizes!(sike_p503_params(
+            Some(P503_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P503_THREE_TORSION_STRATEGY.to_vec()),
+        ));
+        check_sizes!(sike_p610_params(
+            Some(P610_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P610_THREE_TORSION_STRATEGY.to_vec()),
+        ));
+        check_sizes!(sike_p751_params(
+            Some(P751_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P751_THREE_TORSION_STRATEGY.to_vec()),
+        ));
+    }
+
+    #[test]
+    fn test_ffi_buffers_p434() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+        let kem = KEM::setup(params).unwrap();
+
+        let (s, sk3, pk3) = kem.keygen().unwrap();
+        let pk_bytes = FixedPublicKeyP434::from_public_key(pk3.clone())
+            .unwrap()
+            .to_array();
+
+        // Slice-based encapsulation, decapsulated by the Vec-based API
+        let mut ct = vec![0; kem.ciphertext_byte_len()];
+        let mut ss = vec![0; kem.shared_secret_byte_len()];
+        kem.encaps_into(&pk_bytes, &mut ct, &mut ss).unwrap();
+
+        let (c0, c1) = ct.split_at(FixedPublicKeyP434::LEN);
+        let (bytes00, bytes01, bytes02) = kem.decode_fixed(c0).unwrap().into_bytes();
+        let c = Ciphertext {
+            bytes00,
+            bytes01,
+            bytes02,
+            bytes1: c1.to_vec(),
+        };
+        assert_eq!(kem.decaps(&s, &sk3, &pk3, c).unwrap(), ss);
+
+        // Vec-based encapsulation, decapsulated by the slice-based API
+        let (c, k) = kem.encaps(&pk3).unwrap();
+        let c0: PublicKey<QuadraticExtension<PrimeFieldP434>> =
+            PublicKey::from_bytes(&c.bytes00, &c.bytes01, &c.bytes02).unwrap();
+        let ct = [
+            &FixedPublicKeyP434::from_public_key(c0).unwrap().to_array()[..],
+            &c.bytes1,
+        ]
+        .concat();
+
+        let mut ss = vec![0; kem.shared_secret_byte_len()];
+        kem.decaps_into(&s, &sk3.to_bytes(), &pk_bytes, &ct, &mut ss)
+            .unwrap();
+        assert_eq!(ss, k);
+
+        // Buffer lengths are checked
+        let mut short = vec![0; kem.ciphertext_byte_len() - 1];
+        assert!(kem.encaps_into(&pk_bytes, &mut short, &mut ss).is_err());
+        assert!(kem
+            .encaps_into(&pk_bytes[1..], &mut vec![0; ct.len()], &mut ss)
+            .is_err());
+        assert!(kem
+            .decaps_into(&s, &sk3.to_bytes(), &pk_bytes, &ct, &mut ss[1..])
+            .is_err());
+    }
+}
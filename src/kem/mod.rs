@@ -3,77 +3,369 @@
 //! # Examples
 //! ```rust
 //! use rust_sike::{self, KEM};
-//! let params = rust_sike::sike_p434_params(None, None);
+//! let params = rust_sike::sike_p434_params(None, None).unwrap();
 //!
-//! let kem = KEM::setup(params);
+//! let kem = KEM::setup(params).unwrap();
 //!
 //! // Alice runs keygen, publishes pk3. Values s and sk3 are secret
-//! let (s, sk3, pk3) = kem.keygen();
+//! let (s, sk3, pk3) = kem.keygen().unwrap();
 //!
 //! // Bob uses pk3 to derive a key k and encapsulation c
-//! let (c, k) = kem.encaps(&pk3);
+//! let (c, k) = kem.encaps(&pk3).unwrap();
 //!
 //! // Bob sends c to Alice
 //! // Alice uses s, c, sk3 and pk3 to recover k
-//! let k_recovered = kem.decaps(&s, &sk3, &pk3, c);
+//! let k_recovered = kem.decaps(&s, &sk3, &pk3, c).unwrap();
 //!
 //! assert_eq!(k, k_recovered);
 //! ```
+//!
+//! G, H and F hash the encodings of the specification: public keys, `c0` and j with each
+//! coordinate over 𝔽ₚ on ⌈log₂(p) / 8⌉ little-endian bytes (see `interop::pqcrypto`). For the
+//! same message and public key, `c0`, `c1` and the shared secret are then those of the KATs
+//! of the reference implementation, only the byte fields of `Ciphertext` keep the encoding
+//! of this crate.
 
 use crate::{
+    error::SikeError,
     ff::FiniteField,
     isogeny::{PublicKey, PublicParameters, SecretKey},
     pke::{Ciphertext, Message, PKE},
-    utils::{conversion, shake},
+    utils::{
+        conversion,
+        hash::{Shake256Hash, SikeHash},
+        shake,
+    },
 };
 
+mod dynamic;
+mod ffi;
+mod partial;
+mod setup;
+mod timing;
+mod vectors;
+pub use dynamic::{dyn_kem, smoke_test, DynKem};
+pub use partial::PartialDecaps;
+pub use setup::SetupBlob;
+pub use timing::{TimingReport, TimingStats};
+pub use vectors::TestVector;
+
+#[cfg(feature = "rustcrypto")]
+use generic_array::{ArrayLength, GenericArray};
+
+#[cfg(feature = "chacha")]
+use rand_chacha::ChaCha20Rng;
+
+use rand_core::RngCore;
+use subtle::{ConditionallySelectable, ConstantTimeEq};
+
+use zeroize::Zeroize;
+
 use std::fmt::Debug;
 
+/// Domain separation tag used when deriving a 32-byte key from a shared secret
+const SHARED_SECRET_32_TAG: &[u8] = b"rust-sike shared secret 32";
+
+/// Domain separation tag used when deriving an RNG seed from a shared secret
+#[cfg(feature = "chacha")]
+const RNG_SEED_TAG: &[u8] = b"rust-sike chacha20 seed";
+
+/// Domain separation tag prefixed to H when binding the key to a transcript context
+const CONTEXT_TAG: &[u8] = b"rust-sike kem context";
+
+/// Reusable scratch buffers for the hash functions of the KEM
+///
+/// Buffers keep their capacity between operations, and are zeroed after each use since
+/// they hold secret-derived data.
+#[derive(Default)]
+pub struct KemContext {
+    input: Vec<u8>,
+    output: Vec<u8>,
+}
+
+impl KemContext {
+    /// Create an empty context, buffers are allocated on first use
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Zero the buffers, keeping their capacity
+    fn wipe(&mut self) {
+        self.input.zeroize();
+        self.output.zeroize();
+    }
+
+    /// Hash the concatenation of `parts` with `hash` into `len` bytes of the output buffer
+    fn hash(&mut self, parts: &[&[u8]], len: usize, hash: impl FnOnce(&[u8], &mut [u8])) -> &[u8] {
+        let mut output = std::mem::take(&mut self.output);
+        output.clear();
+        output.resize(len, 0);
+        self.hash_into(parts, &mut output, hash);
+        self.output = output;
+
+        &self.output
+    }
+
+    /// Hash the concatenation of `parts` with `hash` into `out`
+    fn hash_into(&mut self, parts: &[&[u8]], out: &mut [u8], hash: impl FnOnce(&[u8], &mut [u8])) {
+        self.input.clear();
+        for part in parts {
+            self.input.extend_from_slice(part);
+        }
+
+        hash(&self.input, out);
+    }
+}
+
+/// Outcome of `KEM::self_test`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SelfTestReport {
+    /// Number of keygen/encaps/decaps rounds run
+    pub iterations: usize,
+
+    /// Rounds where decapsulation recovered a different key
+    pub mismatches: usize,
+
+    /// Rounds where one of the steps returned an error
+    pub errors: usize,
+}
+
+impl SelfTestReport {
+    /// True if every round recovered the encapsulated key
+    pub fn is_success(&self) -> bool {
+        self.mismatches == 0 && self.errors == 0
+    }
+}
+
+/// On-wire sizes of the KEM, in bytes, see `KEM::sizes`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KemSizes {
+    /// Public key, in the fixed-size encoding
+    pub public_key: usize,
+
+    /// Secret key: the rejection value `s` followed by `sk3`, without the public key
+    pub secret_key: usize,
+
+    /// Ciphertext, `c0` in the fixed-size encoding followed by `c1`
+    pub ciphertext: usize,
+
+    /// Shared secret
+    pub shared_secret: usize,
+}
+
+/// Derive a 32-byte key from a shared secret
+///
+/// The output length does not depend on the parameter set, which allows
+/// all parameter sets to be used uniformly (e.g. to key an AEAD)
+pub fn shared_secret_32(k: &[u8]) -> [u8; 32] {
+    let input = conversion::concatenate(&[SHARED_SECRET_32_TAG, k]);
+
+    let mut key = [0; 32];
+    key.copy_from_slice(&shake::shake256(&input, 32));
+    key
+}
+
 /// Key-encapsulation mechanism (ref Algorithm 2, Section 1.3.10)
-pub struct KEM<K> {
+///
+/// The hash functions F, G and H are `H`, SHAKE-256 unless built with `setup_with_hash`.
+pub struct KEM<K, H = Shake256Hash> {
     params: PublicParameters<K>,
-    pke: PKE<K>,
+    pke: PKE<K, H>,
     n: usize,
+    pk_len: usize,
 }
 
 impl<K: FiniteField + Clone + Debug> KEM<K> {
     /// Initialise the KEM
     #[inline]
-    pub fn setup(params: PublicParameters<K>) -> Self {
-        Self {
-            pke: PKE::setup(params.clone()),
+    pub fn setup(params: PublicParameters<K>) -> Result<Self, SikeError> {
+        Self::setup_with_hash(params, Shake256Hash)
+    }
+}
+
+impl<K: FiniteField + Clone + Debug, H: SikeHash> KEM<K, H> {
+    /// Initialise the KEM with `hash` as F, G and H
+    ///
+    /// Only `Shake256Hash` gives the KEM of the specification, e.g. a mock is useful to
+    /// test the transform deterministically.
+    pub fn setup_with_hash(params: PublicParameters<K>, hash: H) -> Result<Self, SikeError> {
+        Ok(Self {
+            pke: PKE::setup_with_hash(params.clone(), hash)?,
             n: params.secparam,
+            pk_len: params.public_key_byte_len()?,
             params,
+        })
+    }
+
+    /// Build the KEM on top of an existing `pke`
+    ///
+    /// `params` must describe the same curve and key spaces as the parameters of `pke`, and
+    /// `pke` must use the default block length, otherwise `InconsistentParameters` is
+    /// returned. Strategies are not compared, the isogenies of `pke` are used as they are.
+    pub fn from_pke(pke: PKE<K, H>, params: PublicParameters<K>) -> Result<Self, SikeError> {
+        params.validate()?;
+
+        let own = pke.params();
+        let same_sizes = own.secparam == params.secparam
+            && pke.block_len() == params.secparam / 8
+            && own.keyspace2 == params.keyspace2
+            && own.keyspace3 == params.keyspace3
+            && own.e2 == params.e2
+            && own.e3 == params.e3;
+        let same_points = [
+            (&own.xp2, &params.xp2),
+            (&own.xq2, &params.xq2),
+            (&own.xr2, &params.xr2),
+            (&own.xp3, &params.xp3),
+            (&own.xq3, &params.xq3),
+            (&own.xr3, &params.xr3),
+        ]
+        .iter()
+        .all(|(x, y)| x.equals(y));
+
+        if !(same_sizes && same_points) {
+            return Err(SikeError::InconsistentParameters);
         }
+
+        Ok(Self {
+            pke,
+            n: params.secparam,
+            pk_len: params.public_key_byte_len()?,
+            params,
+        })
+    }
+
+    /// True if both torsions use the strategy-based walk, see
+    /// `PublicParameters::uses_optimized_walks`
+    pub fn is_optimized(&self) -> bool {
+        self.params.uses_optimized_walks() == (true, true)
+    }
+
+    /// Make decapsulation fail with `InvalidPublicKey` when `c0` is trivial, see
+    /// `PKE::set_reject_trivial_keys`
+    pub fn set_reject_trivial_keys(&mut self, reject: bool) {
+        self.pke.set_reject_trivial_keys(reject);
     }
 
     /// Generate a secret and a keypair
     #[inline]
-    pub fn keygen(&self) -> Result<(Vec<u8>, SecretKey, PublicKey<K>), String> {
+    pub fn keygen(&self) -> Result<(Vec<u8>, SecretKey, PublicKey<K>), SikeError> {
         let sk3 = SecretKey::get_random_secret_key(self.params.keyspace3 as usize)?;
-        let pk3 = self.pke.isogenies.isogen3(&sk3)?;
-        let s = Self::random_string(self.n);
+        let pk3 = self.pke.isogenies.isogen3_untyped(&sk3)?;
+        // The rejection value s is a n-bit string, like the message
+        let s = Self::random_string(self.n / 8);
+
+        Ok((s, sk3, pk3))
+    }
+
+    /// Generate a secret and a keypair, drawing all randomness from `rng`
+    ///
+    /// With a seeded `rng` this is deterministic, e.g. to produce test vectors.
+    pub fn keygen_with_rng<R: RngCore>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(Vec<u8>, SecretKey, PublicKey<K>), SikeError> {
+        let sk3 = SecretKey::random_with_rng(self.params.keyspace3 as usize, rng);
+        let pk3 = self.pke.isogenies.isogen3_untyped(&sk3)?;
+        let mut s = vec![0; self.n / 8];
+        rng.fill_bytes(&mut s);
 
         Ok((s, sk3, pk3))
     }
 
     /// Encapsulate the shared secret using the PKE encryption
+    ///
+    /// Each call allocates a new `KemContext`, see `encaps_with_ctx` to reuse one
+    #[inline]
+    pub fn encaps(&self, pk: &PublicKey<K>) -> Result<(Ciphertext, Vec<u8>), SikeError> {
+        self.encaps_with_ctx(&mut KemContext::new(), pk)
+    }
+
+    /// Encapsulate the shared secret, reusing the buffers of `ctx`
+    #[inline]
+    pub fn encaps_with_ctx(
+        &self,
+        ctx: &mut KemContext,
+        pk: &PublicKey<K>,
+    ) -> Result<(Ciphertext, Vec<u8>), SikeError> {
+        let message = Message::from_bytes(Self::random_string(self.n / 8));
+        self.encaps_inner(ctx, pk, message, None)
+    }
+
+    /// Encapsulate the shared secret, drawing the message from `rng`
+    pub fn encaps_with_rng<R: RngCore>(
+        &self,
+        pk: &PublicKey<K>,
+        rng: &mut R,
+    ) -> Result<(Ciphertext, Vec<u8>), SikeError> {
+        let mut message = vec![0; self.n / 8];
+        rng.fill_bytes(&mut message);
+        self.encaps_inner(
+            &mut KemContext::new(),
+            pk,
+            Message::from_bytes(message),
+            None,
+        )
+    }
+
+    /// Encapsulate the shared secret of a caller-supplied message `m` of `secparam / 8` bytes
+    ///
+    /// The ephemeral secret is still G(m ‖ pk), so the output is fully determined by `m` and
+    /// `pk`. **Testing only, behind the `reference` feature:** a reused `m` reuses the shared
+    /// secret, so `m` must never be chosen this way outside of fixed test vectors.
+    #[cfg(any(test, feature = "reference"))]
+    pub fn encaps_with_message(
+        &self,
+        pk: &PublicKey<K>,
+        m: &Message,
+    ) -> Result<(Ciphertext, Vec<u8>), SikeError> {
+        if m.bytes.len() != self.n / 8 {
+            return Err(SikeError::InvalidMessage(
+                "message length is not secparam / 8",
+            ));
+        }
+
+        self.encaps_inner(&mut KemContext::new(), pk, m.clone(), None)
+    }
+
+    /// Encapsulate a shared secret bound to `context` (e.g. a session transcript hash)
+    ///
+    /// The context is mixed into H with a domain separation tag, so the key only
+    /// matches a `decaps_with_context` call given the same context. An empty context
+    /// is still separated from plain `encaps`.
     #[inline]
-    pub fn encaps(&self, pk: &PublicKey<K>) -> Result<(Ciphertext, Vec<u8>), String> {
+    pub fn encaps_with_context(
+        &self,
+        pk: &PublicKey<K>,
+        context: &[u8],
+    ) -> Result<(Ciphertext, Vec<u8>), SikeError> {
         let message = Message::from_bytes(Self::random_string(self.n / 8));
-        let r = self.hash_function_g(&message.clone(), &pk);
-        let det_sk = SecretKey::from_bytes(&r);
+        self.encaps_inner(&mut KemContext::new(), pk, message, Some(context))
+    }
 
-        let c0: PublicKey<K> = self.pke.isogenies.isogen2(&det_sk)?;
+    fn encaps_inner(
+        &self,
+        ctx: &mut KemContext,
+        pk: &PublicKey<K>,
+        message: Message,
+        context: Option<&[u8]>,
+    ) -> Result<(Ciphertext, Vec<u8>), SikeError> {
+        let det_sk = self.ephemeral_secret(ctx, &message, pk)?;
+
+        let c0: PublicKey<K> = self.pke.isogenies.isogen2_untyped(&det_sk)?;
 
-        let j_inv = self.pke.isogenies.isoex2(&det_sk, &pk)?;
-        let h = self.pke.hash_function_f(j_inv);
+        let j_inv = self.pke.isogenies.isoex2_untyped(&det_sk, &pk)?;
+        let h = self.pke.hash_function_f(j_inv)?;
 
         if h.len() != message.bytes.len() {
-            return Err(String::from("Incorrect Hash"));
+            return Err(SikeError::IncorrectHash);
         }
 
-        let c1_bytes = PKE::<K>::xor(&message.bytes, &h);
+        let c1_bytes = PKE::<K, H>::xor(&message.bytes, &h);
+
+        let c0_bytes = self.public_key_bytes_le(&c0)?;
+        let k = self.hash_function_h(ctx, context, &message, &c0_bytes, &c1_bytes);
+        ctx.wipe();
 
         let (part1, part2, part3) = c0.into_bytes();
         let cipher = Ciphertext {
@@ -83,11 +375,12 @@ impl<K: FiniteField + Clone + Debug> KEM<K> {
             bytes1: c1_bytes,
         };
 
-        let k = self.hash_function_h(&message, &cipher);
         Ok((cipher, k))
     }
 
     /// Decapsulate the shared secret using the PKE decryption
+    ///
+    /// Each call allocates a new `KemContext`, see `decaps_with_ctx` to reuse one
     #[inline]
     pub fn decaps(
         &self,
@@ -95,21 +388,226 @@ impl<K: FiniteField + Clone + Debug> KEM<K> {
         sk: &SecretKey,
         pk: &PublicKey<K>,
         c: Ciphertext,
-    ) -> Result<Vec<u8>, String> {
-        let m = self.pke.dec(&sk, c.clone())?;
-        let s = Message::from_bytes(s.to_vec());
-        let r = self.hash_function_g(&m.clone(), &pk);
+    ) -> Result<Vec<u8>, SikeError> {
+        self.decaps_with_ctx(&mut KemContext::new(), s, sk, pk, c)
+    }
 
+    /// Decapsulate the shared secret, reusing the buffers of `ctx`
+    #[inline]
+    pub fn decaps_with_ctx(
+        &self,
+        ctx: &mut KemContext,
+        s: &[u8],
+        sk: &SecretKey,
+        pk: &PublicKey<K>,
+        c: Ciphertext,
+    ) -> Result<Vec<u8>, SikeError> {
+        self.decaps_inner(ctx, s, sk, pk, c, None)
+    }
+
+    /// Decapsulate a shared secret bound to `context`, see `encaps_with_context`
+    #[inline]
+    pub fn decaps_with_context(
+        &self,
+        s: &[u8],
+        sk: &SecretKey,
+        pk: &PublicKey<K>,
+        c: Ciphertext,
+        context: &[u8],
+    ) -> Result<Vec<u8>, SikeError> {
+        self.decaps_inner(&mut KemContext::new(), s, sk, pk, c, Some(context))
+    }
+
+    fn decaps_inner(
+        &self,
+        ctx: &mut KemContext,
+        s: &[u8],
+        sk: &SecretKey,
+        pk: &PublicKey<K>,
+        c: Ciphertext,
+        context: Option<&[u8]>,
+    ) -> Result<Vec<u8>, SikeError> {
+        let m = self.pke.dec(sk, c.clone())?;
+        self.decaps_message(ctx, s, m, pk, c, context)
+    }
+
+    /// Re-encryption check and key derivation, from the decrypted message `m`
+    fn decaps_message(
+        &self,
+        ctx: &mut KemContext,
+        s: &[u8],
+        m: Message,
+        pk: &PublicKey<K>,
+        c: Ciphertext,
+        context: Option<&[u8]>,
+    ) -> Result<Vec<u8>, SikeError> {
+        if s.len() != m.bytes.len() {
+            return Err(SikeError::InvalidEncoding(
+                "rejection secret length mismatch",
+            ));
+        }
+
+        let c0 = PublicKey::from_bytes(&c.bytes00, &c.bytes01, &c.bytes02)?;
+
+        // H hashes the encoding of the specification, so other encodings of the same c0
+        // (e.g. with leading zeros) must not share its key
+        let (part1, part2, part3) = c0.to_bytes();
+        if part1 != c.bytes00 || part2 != c.bytes01 || part3 != c.bytes02 {
+            return Err(SikeError::NonCanonicalEncoding);
+        }
+
+        let rsk = self.ephemeral_secret(ctx, &m, pk)?;
+
+        let c0p = self.pke.isogenies.isogen2_untyped(&rsk)?;
+
+        // Constant-time selection of m (if c0' = c0) or s (otherwise), hashed by the same code
+        let c0_bytes = self.public_key_bytes_le(&c0)?;
+        let valid = self.public_key_bytes_le(&c0p)?.ct_eq(&c0_bytes);
+        let selected = s
+            .iter()
+            .zip(m.bytes.iter())
+            .map(|(s_i, m_i)| u8::conditional_select(s_i, m_i, valid))
+            .collect();
+        let selected = Message::from_bytes(selected);
+
+        let k = self.hash_function_h(ctx, context, &selected, &c0_bytes, &c.bytes1);
+        ctx.wipe();
+
+        Ok(k)
+    }
+
+    /// Encapsulate the shared secret, returned as a `GenericArray` of `N` bytes
+    ///
+    /// `N` must be the shared secret length, `secparam / 8` bytes: `U16` for SIKEp434,
+    /// `U24` for SIKEp503 and SIKEp610, `U32` for SIKEp751.
+    #[cfg(feature = "rustcrypto")]
+    pub fn encaps_ga<N: ArrayLength<u8>>(
+        &self,
+        pk: &PublicKey<K>,
+    ) -> Result<(Ciphertext, GenericArray<u8, N>), SikeError> {
+        let (c, k) = self.encaps(pk)?;
+        Ok((c, Self::to_generic_array(&k)?))
+    }
+
+    /// Decapsulate the shared secret, returned as a `GenericArray` of `N` bytes (see `encaps_ga`)
+    #[cfg(feature = "rustcrypto")]
+    pub fn decaps_ga<N: ArrayLength<u8>>(
+        &self,
+        s: &[u8],
+        sk: &SecretKey,
+        pk: &PublicKey<K>,
+        c: Ciphertext,
+    ) -> Result<GenericArray<u8, N>, SikeError> {
+        let k = self.decaps(s, sk, pk, c)?;
+        Self::to_generic_array(&k)
+    }
+
+    #[cfg(feature = "rustcrypto")]
+    fn to_generic_array<N: ArrayLength<u8>>(k: &[u8]) -> Result<GenericArray<u8, N>, SikeError> {
+        GenericArray::from_exact_iter(k.iter().copied())
+            .ok_or(SikeError::InvalidEncoding("shared secret length mismatch"))
+    }
+
+    /// Decapsulate the shared secret without the re-encryption check
+    ///
+    /// **WARNING: this skips the Fujisaki-Okamoto re-encryption and is therefore insecure
+    /// against chosen-ciphertext attacks.** It only exists to measure the cost of the
+    /// isogeny layer alone, and must not be used outside of profiling.
+    #[cfg(feature = "unsafe_profiling")]
+    #[inline]
+    pub fn decaps_no_reencrypt(&self, sk: &SecretKey, c: Ciphertext) -> Result<Vec<u8>, SikeError> {
         let c0 = PublicKey::from_bytes(&c.bytes00, &c.bytes01, &c.bytes02)?;
-        let rsk = SecretKey::from_bytes(&r);
+        let m = self.pke.dec(sk, c.clone())?;
+
+        let mut ctx = KemContext::new();
+        let k = self.hash_function_h(
+            &mut ctx,
+            None,
+            &m,
+            &self.public_key_bytes_le(&c0)?,
+            &c.bytes1,
+        );
+        ctx.wipe();
 
-        let c0p = self.pke.isogenies.isogen2(&rsk)?;
+        Ok(k)
+    }
 
-        if c0p == c0 {
-            Ok(self.hash_function_h(&m, &c))
-        } else {
-            Ok(self.hash_function_h(&s, &c))
+    /// Recover the raw j-invariant shared through the ciphertext, before any hashing
+    ///
+    /// **Advanced use only:** this is meant for interoperability with protocols that
+    /// derive keys from the j-invariant themselves. The output is the byte encoding
+    /// that `decaps` would hash with F, and does not benefit from the re-encryption check.
+    pub fn decaps_raw_j(&self, sk: &SecretKey, c: &Ciphertext) -> Result<Vec<u8>, SikeError> {
+        let c0 = PublicKey::from_bytes(&c.bytes00, &c.bytes01, &c.bytes02)?;
+        let j = self.pke.exchange(sk, &c0)?;
+
+        self.pke.j_invariant_bytes(&j)
+    }
+
+    /// Seed a `ChaCha20Rng` from a shared secret
+    ///
+    /// The 32-byte seed is derived from `k` with SHAKE-256 and a dedicated domain separation
+    /// tag, so the stream is unrelated to the keys derived by `kem::shared_secret_32`.
+    #[cfg(feature = "chacha")]
+    pub fn rng_from_shared_secret(&self, k: &[u8]) -> ChaCha20Rng {
+        use rand_core::SeedableRng;
+
+        let input = conversion::concatenate(&[RNG_SEED_TAG, k]);
+
+        let mut seed = [0; 32];
+        shake::shake256_into(&input, &mut seed);
+        ChaCha20Rng::from_seed(seed)
+    }
+
+    /// Run keygen, encaps and decaps `iterations` times and count decapsulation failures
+    ///
+    /// Under honest execution, no failure is expected
+    pub fn self_test(&self, iterations: usize) -> SelfTestReport {
+        let mut report = SelfTestReport {
+            iterations,
+            mismatches: 0,
+            errors: 0,
+        };
+
+        for _ in 0..iterations {
+            match self.self_test_round() {
+                Ok(true) => {}
+                Ok(false) => report.mismatches += 1,
+                Err(_) => report.errors += 1,
+            }
         }
+
+        report
+    }
+
+    /// Single round of `self_test`, returns whether the key was recovered
+    fn self_test_round(&self) -> Result<bool, SikeError> {
+        let (s, sk3, pk3) = self.keygen()?;
+        let (c, k) = self.encaps(&pk3)?;
+        let k_recovered = self.decaps(&s, &sk3, &pk3, c)?;
+
+        Ok(k == k_recovered)
+    }
+
+    /// Variable-size encoding of a public key, as in `TestVector`
+    fn public_key_bytes(pk: PublicKey<K>) -> Vec<u8> {
+        let (part1, part2, part3) = pk.into_bytes();
+        conversion::concatenate(&[&part1, &part2, &part3])
+    }
+
+    /// Encoding of a public key as in the specification, x(P) ‖ x(Q) ‖ x(R) with little-endian
+    /// coordinates, hashed by G and H
+    ///
+    /// All the keys of a parameter set have the same length, so that they can be compared in
+    /// constant time.
+    fn public_key_bytes_le(&self, pk: &PublicKey<K>) -> Result<Vec<u8>, SikeError> {
+        let coord_len = self.pk_len / 6;
+        Ok([
+            pk.x1.to_bytes_le(coord_len)?,
+            pk.x2.to_bytes_le(coord_len)?,
+            pk.x3.to_bytes_le(coord_len)?,
+        ]
+        .concat())
     }
 
     fn random_string(size: usize) -> Vec<u8> {
@@ -118,28 +616,64 @@ impl<K: FiniteField + Clone + Debug> KEM<K> {
         result
     }
 
-    fn hash_function_g(&self, m: &Message, pk: &PublicKey<K>) -> Vec<u8> {
-        let (part1, part2, part3) = pk.clone().into_bytes();
-        let msg_bytes = m.clone().into_bytes();
-        let input = conversion::concatenate(&[&msg_bytes, &part1, &part2, &part3]);
-
-        let n: usize = self.params.secparam;
-
-        shake::shake256(&input, n / 8)
+    /// Ephemeral secret r = G(m ‖ pk) of the encryption, as an integer in [0, 2^e2)
+    ///
+    /// G outputs the key space of sk2 and the bits above e2 are cleared, as `SecretKeyA` in
+    /// the specification.
+    fn ephemeral_secret(
+        &self,
+        ctx: &mut KemContext,
+        m: &Message,
+        pk: &PublicKey<K>,
+    ) -> Result<SecretKey, SikeError> {
+        let r = SecretKey::from_bytes_clamped(self.hash_function_g(ctx, m, pk)?, self.params.e2);
+        ctx.wipe();
+        Ok(r)
     }
 
-    fn hash_function_h(&self, m: &Message, c: &Ciphertext) -> Vec<u8> {
-        let input = conversion::concatenate(&[
-            &m.clone().into_bytes(),
-            &c.bytes00,
-            &c.bytes01,
-            &c.bytes02,
-            &c.bytes1,
-        ]);
+    /// G(m, pk), over the encoding of `pk` of the specification
+    fn hash_function_g<'a>(
+        &self,
+        ctx: &'a mut KemContext,
+        m: &Message,
+        pk: &PublicKey<K>,
+    ) -> Result<&'a [u8], SikeError> {
+        let pk_bytes = self.public_key_bytes_le(pk)?;
+
+        Ok(ctx.hash(
+            &[&m.bytes, &pk_bytes],
+            self.params.keyspace2 as usize,
+            |input, out| self.pke.hash().g(input, out),
+        ))
+    }
 
-        let n = self.params.secparam;
+    /// H(m, c0 ‖ c1), `c0` in the encoding of the specification (see `public_key_bytes_le`),
+    /// prefixed by a tag and the length-framed `context` when one is given
+    ///
+    /// Hashed directly into the returned shared secret, only the input goes through `ctx`
+    fn hash_function_h(
+        &self,
+        ctx: &mut KemContext,
+        context: Option<&[u8]>,
+        m: &Message,
+        c0: &[u8],
+        c1: &[u8],
+    ) -> Vec<u8> {
+        let mut k = vec![0; self.params.secparam / 8];
+        let parts = [&m.bytes[..], c0, c1];
+
+        let h = |input: &[u8], out: &mut [u8]| self.pke.hash().h(input, out);
+
+        match context {
+            None => ctx.hash_into(&parts, &mut k, h),
+            Some(context) => {
+                let len = (context.len() as u64).to_le_bytes();
+                let prefix = [CONTEXT_TAG, &len, context];
+                ctx.hash_into(&[&prefix[..], &parts[..]].concat(), &mut k, h)
+            }
+        }
 
-        shake::shake256(&input, n / 8)
+        k
     }
 }
 
@@ -155,7 +689,7 @@ mod tests {
     fn test_kem_p434() {
         let params = sike_p434_params(None, None).unwrap();
 
-        let kem = KEM::setup(params);
+        let kem = KEM::setup(params).unwrap();
 
         // Alice runs keygen, publishes pk3. Values s and sk3 are secret
         let (s, sk3, pk3) = kem.keygen().unwrap();
@@ -174,7 +708,7 @@ mod tests {
     fn test_kem_p503() {
         let params = sike_p503_params(None, None).unwrap();
 
-        let kem = KEM::setup(params);
+        let kem = KEM::setup(params).unwrap();
 
         // Alice runs keygen, publishes pk3. Values s and sk3 are secret
         let (s, sk3, pk3) = kem.keygen().unwrap();
@@ -193,7 +727,7 @@ mod tests {
     fn test_kem_p610() {
         let params = sike_p610_params(None, None).unwrap();
 
-        let kem = KEM::setup(params);
+        let kem = KEM::setup(params).unwrap();
 
         // Alice runs keygen, publishes pk3. Values s and sk3 are secret
         let (s, sk3, pk3) = kem.keygen().unwrap();
@@ -212,7 +746,7 @@ mod tests {
     fn test_kem_p751() {
         let params = sike_p751_params(None, None).unwrap();
 
-        let kem = KEM::setup(params);
+        let kem = KEM::setup(params).unwrap();
 
         // Alice runs keygen, publishes pk3. Values s and sk3 are secret
         let (s, sk3, pk3) = kem.keygen().unwrap();
@@ -235,7 +769,7 @@ mod tests {
         )
         .unwrap();
 
-        let kem = KEM::setup(params);
+        let kem = KEM::setup(params).unwrap();
 
         // Alice runs keygen, publishes pk3. Values s and sk3 are secret
         let (s, sk3, pk3) = kem.keygen().unwrap();
@@ -258,7 +792,7 @@ mod tests {
         )
         .unwrap();
 
-        let kem = KEM::setup(params);
+        let kem = KEM::setup(params).unwrap();
 
         // Alice runs keygen, publishes pk3. Values s and sk3 are secret
         let (s, sk3, pk3) = kem.keygen().unwrap();
@@ -281,7 +815,7 @@ mod tests {
         )
         .unwrap();
 
-        let kem = KEM::setup(params);
+        let kem = KEM::setup(params).unwrap();
 
         // Alice runs keygen, publishes pk3. Values s and sk3 are secret
         let (s, sk3, pk3) = kem.keygen().unwrap();
@@ -304,7 +838,7 @@ mod tests {
         )
         .unwrap();
 
-        let kem = KEM::setup(params);
+        let kem = KEM::setup(params).unwrap();
 
         // Alice runs keygen, publishes pk3. Values s and sk3 are secret
         let (s, sk3, pk3) = kem.keygen().unwrap();
@@ -318,4 +852,598 @@ mod tests {
 
         assert_eq!(k, k_recovered);
     }
+
+    #[test]
+    fn test_kem_is_optimized() {
+        let params = sike_p434_params(None, None).unwrap();
+        assert_eq!(params.uses_optimized_walks(), (false, false));
+        assert!(!KEM::setup(params).unwrap().is_optimized());
+
+        let params = sike_p434_params(Some(P434_TWO_TORSION_STRATEGY.to_vec()), None).unwrap();
+        assert_eq!(params.uses_optimized_walks(), (true, false));
+        assert!(!KEM::setup(params).unwrap().is_optimized());
+
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+        assert_eq!(params.uses_optimized_walks(), (true, true));
+        assert!(KEM::setup(params).unwrap().is_optimized());
+    }
+
+    #[test]
+    fn test_kem_encaps_with_message_p434() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+        let kem = KEM::setup(params).unwrap();
+        let (s, sk3, pk3) = kem.keygen().unwrap();
+
+        let m = Message::from_bytes((0..16).collect());
+        let (c, k) = kem.encaps_with_message(&pk3, &m).unwrap();
+        let (c_again, k_again) = kem.encaps_with_message(&pk3, &m).unwrap();
+
+        assert_eq!(c.bytes00, c_again.bytes00);
+        assert_eq!(c.bytes01, c_again.bytes01);
+        assert_eq!(c.bytes02, c_again.bytes02);
+        assert_eq!(c.bytes1, c_again.bytes1);
+        assert_eq!(k, k_again);
+        assert_eq!(kem.decaps(&s, &sk3, &pk3, c).unwrap(), k);
+
+        let short = Message::from_bytes(vec![0; 15]);
+        assert!(matches!(
+            kem.encaps_with_message(&pk3, &short),
+            Err(SikeError::InvalidMessage(_))
+        ));
+    }
+
+    /// Runs the KEM with the simple walks and with the strategies from the same seed, and
+    /// checks that every output is identical, and that each decapsulates the other's
+    fn assert_kem_matches_across_strategies<K: FiniteField + Clone + Debug>(
+        simple: &KEM<K>,
+        optim: &KEM<K>,
+        seed: &[u8],
+    ) {
+        use crate::utils::shake::ShakeRng;
+
+        let run = |kem: &KEM<K>| {
+            let mut rng = ShakeRng::new(seed);
+            let (s, sk3, pk3) = kem.keygen_with_rng(&mut rng).unwrap();
+            let (c, k) = kem.encaps_with_rng(&pk3, &mut rng).unwrap();
+            (s, sk3, pk3, c, k)
+        };
+
+        let (s, sk3, pk3, c, k) = run(simple);
+        let (s_opt, sk3_opt, pk3_opt, c_opt, k_opt) = run(optim);
+
+        assert_eq!(sk3, sk3_opt);
+        assert_eq!(pk3, pk3_opt);
+        assert_eq!(c.bytes00, c_opt.bytes00);
+        assert_eq!(c.bytes1, c_opt.bytes1);
+        assert_eq!(k, k_opt);
+
+        assert_eq!(optim.decaps(&s, &sk3, &pk3, c).unwrap(), k);
+        assert_eq!(simple.decaps(&s_opt, &sk3_opt, &pk3_opt, c_opt).unwrap(), k);
+    }
+
+    #[test]
+    fn test_kem_across_strategies_p434() {
+        let simple = KEM::setup(sike_p434_params(None, None).unwrap()).unwrap();
+        let optim = KEM::setup(
+            sike_p434_params(
+                Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+                Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_kem_matches_across_strategies(&simple, &optim, b"strategies p434");
+    }
+
+    #[test]
+    fn test_kem_across_strategies_p503() {
+        let simple = KEM::setup(sike_p503_params(None, None).unwrap()).unwrap();
+        let optim = KEM::setup(
+            sike_p503_params(
+                Some(P503_TWO_TORSION_STRATEGY.to_vec()),
+                Some(P503_THREE_TORSION_STRATEGY.to_vec()),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_kem_matches_across_strategies(&simple, &optim, b"strategies p503");
+    }
+
+    /// Checks that keygen draws a rejection secret `s` of `secparam / 8` bytes
+    fn assert_rejection_secret_length<K: FiniteField + Clone + Debug>(params: PublicParameters<K>) {
+        let secparam = params.secparam;
+
+        let kem = KEM::setup(params).unwrap();
+        let (s, _, _) = kem.keygen().unwrap();
+
+        assert_eq!(s.len(), secparam / 8);
+    }
+
+    #[test]
+    fn test_rejection_secret_length() {
+        assert_rejection_secret_length(sike_p434_params(None, None).unwrap());
+        assert_rejection_secret_length(sike_p503_params(None, None).unwrap());
+        assert_rejection_secret_length(sike_p610_params(None, None).unwrap());
+        assert_rejection_secret_length(sike_p751_params(None, None).unwrap());
+    }
+
+    #[cfg(feature = "rustcrypto")]
+    #[test]
+    fn test_kem_generic_array_p751() {
+        use aes_gcm::{
+            aead::{Aead, NewAead},
+            Aes256Gcm, Nonce,
+        };
+        use generic_array::typenum::{U16, U32};
+
+        let params = sike_p751_params(
+            Some(P751_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P751_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+
+        let kem = KEM::setup(params).unwrap();
+
+        let (s, sk3, pk3) = kem.keygen().unwrap();
+        let (c, key) = kem.encaps_ga::<U32>(&pk3).unwrap();
+        let key_recovered = kem.decaps_ga::<U32>(&s, &sk3, &pk3, c.clone()).unwrap();
+
+        assert_eq!(key, key_recovered);
+        assert!(kem.decaps_ga::<U16>(&s, &sk3, &pk3, c).is_err());
+
+        let nonce = Nonce::from_slice(&[0; 12]);
+        let ciphertext = Aes256Gcm::new(&key)
+            .encrypt(nonce, &b"rust-sike"[..])
+            .unwrap();
+        let plaintext = Aes256Gcm::new(&key_recovered)
+            .decrypt(nonce, ciphertext.as_ref())
+            .unwrap();
+
+        assert_eq!(plaintext, b"rust-sike");
+    }
+
+    #[test]
+    fn test_decaps_selection_p434() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+
+        let kem = KEM::setup(params).unwrap();
+        let mut ctx = KemContext::new();
+
+        let (s, sk3, pk3) = kem.keygen().unwrap();
+        let (c, k) = kem.encaps(&pk3).unwrap();
+
+        // Valid ciphertext: H(m, c)
+        let k_recovered = kem.decaps(&s, &sk3, &pk3, c.clone()).unwrap();
+        assert_eq!(k, k_recovered);
+
+        // Tampered ciphertext: H(s, c')
+        let mut tampered = c;
+        tampered.bytes1[0] ^= 1;
+        let k_rejected = kem.decaps(&s, &sk3, &pk3, tampered.clone()).unwrap();
+        let c0 =
+            PublicKey::from_bytes(&tampered.bytes00, &tampered.bytes01, &tampered.bytes02).unwrap();
+        let expected = kem.hash_function_h(
+            &mut ctx,
+            None,
+            &Message::from_bytes(s.clone()),
+            &kem.public_key_bytes_le(&c0).unwrap(),
+            &tampered.bytes1,
+        );
+
+        assert_eq!(k_rejected, expected);
+        assert_ne!(k_rejected, k);
+    }
+
+    #[test]
+    fn test_kem_from_pke_p434() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+
+        let pke = PKE::setup(params.clone()).unwrap();
+        let kem = KEM::from_pke(pke, params.clone()).unwrap();
+
+        let (s, sk3, pk3) = kem.keygen().unwrap();
+        let (c, k) = kem.encaps(&pk3).unwrap();
+        let k_recovered = kem.decaps(&s, &sk3, &pk3, c).unwrap();
+
+        assert_eq!(k, k_recovered);
+
+        // Parameters of another curve basis are rejected
+        let mut other = params.clone();
+        other.xp2 = params.xq2.clone();
+        let pke = PKE::setup(params).unwrap();
+        assert!(matches!(
+            KEM::from_pke(pke, other),
+            Err(SikeError::InconsistentParameters)
+        ));
+    }
+
+    #[test]
+    fn test_kem_context_p434() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+
+        let kem = KEM::setup(params).unwrap();
+        let mut ctx = KemContext::new();
+
+        let (s, sk3, pk3) = kem.keygen().unwrap();
+
+        // The same context is reused across operations
+        for _ in 0..3 {
+            let (c, k) = kem.encaps_with_ctx(&mut ctx, &pk3).unwrap();
+
+            let k_ctx = kem
+                .decaps_with_ctx(&mut ctx, &s, &sk3, &pk3, c.clone())
+                .unwrap();
+            let k_alloc = kem.decaps(&s, &sk3, &pk3, c.clone()).unwrap();
+
+            assert_eq!(k, k_ctx);
+            assert_eq!(k_ctx, k_alloc);
+
+            let msg = Message::from_bytes(vec![0xAB; 16]);
+            let c0 = PublicKey::from_bytes(&c.bytes00, &c.bytes01, &c.bytes02).unwrap();
+            let c0_bytes = kem.public_key_bytes_le(&c0).unwrap();
+            let expected = shake::shake256(
+                &conversion::concatenate(&[&msg.bytes, &c0_bytes, &c.bytes1]),
+                16,
+            );
+            assert_eq!(
+                kem.hash_function_h(&mut ctx, None, &msg, &c0_bytes, &c.bytes1),
+                expected
+            );
+        }
+
+        ctx.wipe();
+        assert!(ctx.input.is_empty() && ctx.output.is_empty());
+    }
+
+    #[test]
+    fn test_kem_context_binding_p434() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+
+        let kem = KEM::setup(params).unwrap();
+        let (s, sk3, pk3) = kem.keygen().unwrap();
+
+        let (c, k) = kem.encaps_with_context(&pk3, b"transcript A").unwrap();
+
+        let k_same = kem
+            .decaps_with_context(&s, &sk3, &pk3, c.clone(), b"transcript A")
+            .unwrap();
+        let k_other = kem
+            .decaps_with_context(&s, &sk3, &pk3, c.clone(), b"transcript B")
+            .unwrap();
+        let k_empty = kem
+            .decaps_with_context(&s, &sk3, &pk3, c.clone(), b"")
+            .unwrap();
+        let k_plain = kem.decaps(&s, &sk3, &pk3, c).unwrap();
+
+        assert_eq!(k, k_same);
+        assert_ne!(k, k_other);
+        assert_ne!(k, k_plain);
+        assert_ne!(k_empty, k_plain);
+    }
+
+    #[test]
+    fn test_decaps_raw_j_p434() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+
+        let kem = KEM::setup(params).unwrap();
+
+        let (_, sk3, pk3) = kem.keygen().unwrap();
+        let (c, _) = kem.encaps(&pk3).unwrap();
+
+        let raw_j = kem.decaps_raw_j(&sk3, &c).unwrap();
+
+        let c0 = PublicKey::from_bytes(&c.bytes00, &c.bytes01, &c.bytes02).unwrap();
+        let j = kem.pke.isogenies.isoex3_untyped(&sk3, &c0).unwrap();
+
+        assert_eq!(raw_j, j.to_bytes_le(55).unwrap());
+    }
+
+    #[test]
+    fn test_self_test_p434() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+
+        let kem = KEM::setup(params).unwrap();
+
+        let report = kem.self_test(100);
+
+        assert_eq!(report.iterations, 100);
+        assert_eq!(report.mismatches, 0);
+        assert_eq!(report.errors, 0);
+        assert!(report.is_success());
+    }
+
+    #[cfg(feature = "chacha")]
+    #[test]
+    fn test_rng_from_shared_secret_p434() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+
+        let kem = KEM::setup(params).unwrap();
+
+        let (s, sk3, pk3) = kem.keygen().unwrap();
+        let (c, k) = kem.encaps(&pk3).unwrap();
+        let k_recovered = kem.decaps(&s, &sk3, &pk3, c).unwrap();
+
+        let mut rng = kem.rng_from_shared_secret(&k);
+        let mut rng_recovered = kem.rng_from_shared_secret(&k_recovered);
+
+        let mut stream = [0; 64];
+        let mut stream_recovered = [0; 64];
+        rng.fill_bytes(&mut stream);
+        rng_recovered.fill_bytes(&mut stream_recovered);
+        assert_eq!(stream[..], stream_recovered[..]);
+
+        let mut other = kem.rng_from_shared_secret(&s);
+        other.fill_bytes(&mut stream_recovered);
+        assert_ne!(stream[..], stream_recovered[..]);
+    }
+
+    /// Runs the KEM and checks that both sides derive the same 32-byte key
+    fn assert_shared_secret_32_matches<K: FiniteField + Clone + Debug>(kem: &KEM<K>) {
+        let (s, sk3, pk3) = kem.keygen().unwrap();
+        let (c, k) = kem.encaps(&pk3).unwrap();
+        let k_recovered = kem.decaps(&s, &sk3, &pk3, c).unwrap();
+
+        assert_eq!(shared_secret_32(&k), shared_secret_32(&k_recovered));
+        assert_ne!(shared_secret_32(&k), shared_secret_32(&s));
+    }
+
+    #[test]
+    fn test_shared_secret_32() {
+        assert_shared_secret_32_matches(
+            &KEM::setup(
+                sike_p434_params(
+                    Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+                    Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+                )
+                .unwrap(),
+            )
+            .unwrap(),
+        );
+        assert_shared_secret_32_matches(
+            &KEM::setup(
+                sike_p503_params(
+                    Some(P503_TWO_TORSION_STRATEGY.to_vec()),
+                    Some(P503_THREE_TORSION_STRATEGY.to_vec()),
+                )
+                .unwrap(),
+            )
+            .unwrap(),
+        );
+        assert_shared_secret_32_matches(
+            &KEM::setup(
+                sike_p610_params(
+                    Some(P610_TWO_TORSION_STRATEGY.to_vec()),
+                    Some(P610_THREE_TORSION_STRATEGY.to_vec()),
+                )
+                .unwrap(),
+            )
+            .unwrap(),
+        );
+        assert_shared_secret_32_matches(
+            &KEM::setup(
+                sike_p751_params(
+                    Some(P751_TWO_TORSION_STRATEGY.to_vec()),
+                    Some(P751_THREE_TORSION_STRATEGY.to_vec()),
+                )
+                .unwrap(),
+            )
+            .unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_decaps_reject_trivial_keys() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+        let mut kem = KEM::setup(params.clone()).unwrap();
+        let (s, sk3, pk3) = kem.keygen().unwrap();
+
+        // c0 is the unmoved basis of the 2-torsion
+        let c0 = PublicKey::from_torsion_points(params.xp2, params.xq2, params.xr2);
+        let (bytes00, bytes01, bytes02) = c0.into_bytes();
+        let c = Ciphertext {
+            bytes00,
+            bytes01,
+            bytes02,
+            bytes1: vec![0; params.secparam / 8],
+        };
+
+        assert!(kem.decaps(&s, &sk3, &pk3, c.clone()).is_ok());
+
+        kem.set_reject_trivial_keys(true);
+        assert!(matches!(
+            kem.decaps(&s, &sk3, &pk3, c),
+            Err(SikeError::InvalidPublicKey)
+        ));
+
+        let (c, k) = kem.encaps(&pk3).unwrap();
+        assert_eq!(kem.decaps(&s, &sk3, &pk3, c).unwrap(), k);
+    }
+
+    #[test]
+    fn test_decaps_invalid_c0() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+        let kem = KEM::setup(params).unwrap();
+        let (s, sk3, pk3) = kem.keygen().unwrap();
+        let (c, _) = kem.encaps(&pk3).unwrap();
+
+        // x(P) = 0 does not define a curve
+        let (zero, _, _) =
+            PublicKey::from_torsion_points(pk3.x1.sub(&pk3.x1), pk3.x2.clone(), pk3.x3.clone())
+                .into_bytes();
+        // Leading zeros in each half decode to the same c0, which H hashes in its own encoding
+        let half = c.bytes00.len() / 2;
+        let mut padded = c.clone();
+        padded.bytes00 = [&[0][..], &c.bytes00[..half], &[0], &c.bytes00[half..]].concat();
+        assert!(kem.pke.dec(&sk3, padded.clone()).is_ok());
+        assert!(matches!(
+            kem.decaps(&s, &sk3, &pk3, padded),
+            Err(SikeError::NonCanonicalEncoding)
+        ));
+
+        let mut corrupted = c;
+        corrupted.bytes00 = zero;
+
+        assert!(matches!(
+            kem.pke.dec(&sk3, corrupted.clone()),
+            Err(SikeError::InvalidPublicKey)
+        ));
+        assert!(matches!(
+            kem.decaps(&s, &sk3, &pk3, corrupted),
+            Err(SikeError::InvalidPublicKey)
+        ));
+    }
+
+    #[test]
+    fn test_encaps_known_answer_p434() {
+        use crate::interop::{
+            kat::{P434_KAT0_CT, P434_KAT0_MSG, P434_KAT0_PK, P434_KAT0_SK, P434_KAT0_SS},
+            pqcrypto::{public_key_from_pqcrypto_bytes, to_pqcrypto_bytes},
+        };
+
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+        let kem = KEM::setup(params).unwrap();
+
+        let pk3 = public_key_from_pqcrypto_bytes(&hex::decode(P434_KAT0_PK).unwrap()).unwrap();
+        let m = Message::from_bytes(hex::decode(P434_KAT0_MSG).unwrap());
+
+        let (c, k) = kem.encaps_with_message(&pk3, &m).unwrap();
+        let c0 = PublicKey::from_bytes(&c.bytes00, &c.bytes01, &c.bytes02).unwrap();
+        let ct = [&to_pqcrypto_bytes(&c0).unwrap()[..], &c.bytes1].concat();
+
+        assert_eq!(ct, hex::decode(P434_KAT0_CT).unwrap());
+        assert_eq!(k, hex::decode(P434_KAT0_SS).unwrap());
+
+        // The secret key of the KAT recovers the shared secret
+        let sk = hex::decode(P434_KAT0_SK).unwrap();
+        let sk3 = SecretKey::from_bytes(&sk[16..44]);
+        assert_eq!(kem.decaps(&sk[..16], &sk3, &pk3, c).unwrap(), k);
+    }
+
+    /// Mock of F, G and H repeating their input over the output
+    struct RepeatHash;
+
+    impl SikeHash for RepeatHash {
+        fn f(&self, j: &[u8], out: &mut [u8]) {
+            Self::repeat(j, out);
+        }
+
+        fn g(&self, input: &[u8], out: &mut [u8]) {
+            Self::repeat(input, out);
+        }
+
+        fn h(&self, input: &[u8], out: &mut [u8]) {
+            Self::repeat(input, out);
+        }
+    }
+
+    impl RepeatHash {
+        fn repeat(input: &[u8], out: &mut [u8]) {
+            for (o, i) in out.iter_mut().zip(input.iter().cycle()) {
+                *o = *i;
+            }
+        }
+    }
+
+    #[test]
+    fn test_kem_mock_hash_p434() {
+        use crate::utils::shake::ShakeRng;
+
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+        let kem = KEM::setup_with_hash(params, RepeatHash).unwrap();
+
+        let mut rng = ShakeRng::new(b"mock hash");
+        let (s, sk3, pk3) = kem.keygen_with_rng(&mut rng).unwrap();
+        let m = Message::from_bytes(vec![0xA5; 16]);
+
+        let (c, k) = kem
+            .encaps_inner(&mut KemContext::new(), &pk3, m.clone(), None)
+            .unwrap();
+
+        // H(m ‖ c) truncated to the length of m is m, and F(j) the start of j
+        assert_eq!(k, m.bytes);
+        let j = kem.decaps_raw_j(&sk3, &c).unwrap();
+        let unmasked: Vec<u8> = c.bytes1.iter().zip(&j).map(|(c, j)| c ^ j).collect();
+        assert_eq!(unmasked, m.bytes);
+
+        // G is deterministic, so is the whole encapsulation
+        let (c_again, _) = kem
+            .encaps_inner(&mut KemContext::new(), &pk3, m, None)
+            .unwrap();
+        assert_eq!(c_again.bytes1, c.bytes1);
+        assert_eq!(c_again.bytes00, c.bytes00);
+
+        assert_eq!(kem.decaps(&s, &sk3, &pk3, c).unwrap(), k);
+    }
+
+    #[test]
+    fn test_ephemeral_secret_keyspace() {
+        // e2 = 250: 32 bytes, of which the top 6 bits are cleared
+        let params = sike_p503_params(None, None).unwrap();
+        let kem = KEM::setup(params.clone()).unwrap();
+        let (_, pk3) = kem.pke.gen().unwrap();
+
+        for i in 0..16 {
+            let m = Message::from_bytes(vec![i; params.secparam / 8]);
+            let r = kem
+                .ephemeral_secret(&mut KemContext::new(), &m, &pk3)
+                .unwrap();
+
+            assert_eq!(r.to_bytes().len(), params.keyspace2 as usize);
+            assert!(r.to_integer().significant_bits() <= params.e2 as u32);
+        }
+    }
 }
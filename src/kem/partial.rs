@@ -0,0 +1,115 @@
+//! Decapsulation in two steps, for ciphertexts received in fragments
+
+use super::{KemContext, KEM};
+use crate::{
+    error::SikeError,
+    ff::FiniteField,
+    isogeny::{PublicKey, SecretKey},
+    pke::{Ciphertext, Message, PKE},
+    utils::hash::{Shake256Hash, SikeHash},
+};
+
+use zeroize::Zeroizing;
+
+use std::fmt::Debug;
+
+/// Decapsulation started from `c0`, waiting for the rest of the ciphertext
+///
+/// Holds the mask `h` derived from the shared j-invariant, which is secret and zeroed on drop,
+/// whether or not `finish` is called.
+pub struct PartialDecaps<'a, K, H = Shake256Hash> {
+    kem: &'a KEM<K, H>,
+    bytes00: Vec<u8>,
+    bytes01: Vec<u8>,
+    bytes02: Vec<u8>,
+    h: Zeroizing<Vec<u8>>,
+}
+
+impl<K: FiniteField + Clone + Debug, H: SikeHash> KEM<K, H> {
+    /// Start decapsulating from the first part of the ciphertext, `c0`
+    ///
+    /// This performs the isogeny exchange, which is the expensive step of `decaps`, so that
+    /// it can overlap with the reception of `c1`. See `PartialDecaps::finish`.
+    pub fn decaps_begin(
+        &self,
+        sk: &SecretKey,
+        bytes00: &[u8],
+        bytes01: &[u8],
+        bytes02: &[u8],
+    ) -> Result<PartialDecaps<'_, K, H>, SikeError> {
+        let c0 = PublicKey::from_bytes(bytes00, bytes01, bytes02)?;
+        let j = self.pke.exchange(sk, &c0)?;
+
+        Ok(PartialDecaps {
+            kem: self,
+            bytes00: bytes00.to_vec(),
+            bytes01: bytes01.to_vec(),
+            bytes02: bytes02.to_vec(),
+            h: Zeroizing::new(self.pke.hash_function_f(j)?),
+        })
+    }
+}
+
+impl<K: FiniteField + Clone + Debug, H: SikeHash> PartialDecaps<'_, K, H> {
+    /// Complete the decapsulation with the second part of the ciphertext, `c1`
+    ///
+    /// The output is the one of `decaps` on the whole ciphertext, including the
+    /// re-encryption check.
+    pub fn finish(self, bytes1: &[u8], s: &[u8], pk: &PublicKey<K>) -> Result<Vec<u8>, SikeError> {
+        if self.h.len() != bytes1.len() {
+            return Err(SikeError::IncorrectHash);
+        }
+
+        let m = Message::from_bytes(PKE::<K, H>::xor(&self.h, bytes1));
+
+        let c = Ciphertext {
+            bytes00: self.bytes00,
+            bytes01: self.bytes01,
+            bytes02: self.bytes02,
+            bytes1: bytes1.to_vec(),
+        };
+
+        self.kem
+            .decaps_message(&mut KemContext::new(), s, m, pk, c, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{isogeny::sike_p434_params, utils::strategy::*};
+
+    #[test]
+    fn test_partial_decaps_p434() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+
+        let kem = KEM::setup(params).unwrap();
+        let (s, sk3, pk3) = kem.keygen().unwrap();
+        let (c, k) = kem.encaps(&pk3).unwrap();
+
+        let partial = kem
+            .decaps_begin(&sk3, &c.bytes00, &c.bytes01, &c.bytes02)
+            .unwrap();
+        let k_split = partial.finish(&c.bytes1, &s, &pk3).unwrap();
+
+        assert_eq!(k_split, k);
+        assert_eq!(k_split, kem.decaps(&s, &sk3, &pk3, c.clone()).unwrap());
+
+        // A tampered c1 is rejected implicitly, as with decaps
+        let mut bytes1 = c.bytes1.clone();
+        bytes1[0] ^= 1;
+        let partial = kem
+            .decaps_begin(&sk3, &c.bytes00, &c.bytes01, &c.bytes02)
+            .unwrap();
+        assert_ne!(partial.finish(&bytes1, &s, &pk3).unwrap(), k);
+
+        let partial = kem
+            .decaps_begin(&sk3, &c.bytes00, &c.bytes01, &c.bytes02)
+            .unwrap();
+        assert!(partial.finish(&bytes1[1..], &s, &pk3).is_err());
+    }
+}
@@ -0,0 +1,248 @@
+//! Snapshot of a KEM setup, to restore it without recomputing the strategies
+//!
+//! A `SetupBlob` holds the public parameters, big endian, in this order: a version byte,
+//! `secparam`, `keyspace2`, `keyspace3`, `e2` and `e3` on 8 bytes each, the 2-torsion
+//! then the 3-torsion strategy, and the points `xp2`, `xq2`, `xr2`, `xp3`, `xq3`, `xr3`.
+//! A strategy is a presence byte followed, if present, by its length and its elements on
+//! 4 bytes each. A point is its length on 4 bytes followed by its `into_bytes` encoding.
+
+use super::KEM;
+use crate::{
+    error::SikeError,
+    ff::FiniteField,
+    isogeny::PublicParameters,
+    pke::PKE,
+    utils::{conversion::concatenate, strategy},
+};
+
+use std::{convert::TryFrom, fmt::Debug};
+
+/// Version of the layout of `SetupBlob`
+const SETUP_VERSION: u8 = 1;
+
+/// Serialized setup of a KEM, see `KEM::export_setup`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SetupBlob(Vec<u8>);
+
+impl SetupBlob {
+    /// Wrap bytes produced by `as_bytes`, they are only parsed by `KEM::import_setup`
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    /// Bytes of the blob
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<K: FiniteField + Clone + Debug> KEM<K> {
+    /// Serialize the public parameters of the KEM, including the strategies
+    pub fn export_setup(&self) -> Result<SetupBlob, SikeError> {
+        let params = &self.params;
+        let secparam = params.secparam as u64;
+
+        let mut parts = vec![vec![SETUP_VERSION]];
+        for n in [
+            secparam,
+            params.keyspace2,
+            params.keyspace3,
+            params.e2,
+            params.e3,
+        ]
+        .iter()
+        {
+            parts.push(n.to_be_bytes().to_vec());
+        }
+        parts.push(encode_strategy(&params.e2_strategy)?);
+        parts.push(encode_strategy(&params.e3_strategy)?);
+        for x in [
+            &params.xp2,
+            &params.xq2,
+            &params.xr2,
+            &params.xp3,
+            &params.xq3,
+            &params.xr3,
+        ]
+        .iter()
+        {
+            let bytes = (*x).clone().into_bytes();
+            parts.push(concatenate(&[&encode_len(bytes.len())?, &bytes]));
+        }
+
+        let parts = parts.iter().map(Vec::as_slice).collect::<Vec<_>>();
+        Ok(SetupBlob(concatenate(&parts)))
+    }
+
+    /// Restore a KEM from `export_setup`, the strategies are used as they are
+    ///
+    /// The parameters are validated as by `setup`, and the strategies are checked to have the
+    /// shape the walks expect.
+    pub fn import_setup(blob: &SetupBlob) -> Result<Self, SikeError> {
+        let mut reader = Reader(blob.as_bytes());
+
+        if reader.take(1)? != [SETUP_VERSION] {
+            return Err(SikeError::InvalidEncoding("unknown setup version"));
+        }
+
+        let secparam = usize::try_from(reader.u64()?)
+            .map_err(|_| SikeError::InvalidEncoding("security parameter too large"))?;
+        let keyspace2 = reader.u64()?;
+        let keyspace3 = reader.u64()?;
+        let e2 = reader.u64()?;
+        let e3 = reader.u64()?;
+        let e2_strategy = reader.strategy()?;
+        let e3_strategy = reader.strategy()?;
+
+        let params = PublicParameters {
+            secparam,
+            keyspace2,
+            keyspace3,
+            e2_strategy,
+            e3_strategy,
+            e2,
+            e3,
+            xp2: reader.point()?,
+            xq2: reader.point()?,
+            xr2: reader.point()?,
+            xp3: reader.point()?,
+            xq3: reader.point()?,
+            xr3: reader.point()?,
+        };
+
+        if !reader.0.is_empty() {
+            return Err(SikeError::InvalidEncoding("trailing bytes after setup"));
+        }
+
+        if let Some(strat) = &params.e2_strategy {
+            strategy::validate_shape(strat, (params.e2 / 2) as usize)?;
+        }
+        if let Some(strat) = &params.e3_strategy {
+            strategy::validate_shape(strat, params.e3 as usize)?;
+        }
+
+        let pke = PKE::setup(params.clone())?;
+        Self::from_pke(pke, params)
+    }
+}
+
+/// Length on 4 bytes, big endian
+fn encode_len(len: usize) -> Result<[u8; 4], SikeError> {
+    u32::try_from(len)
+        .map(u32::to_be_bytes)
+        .map_err(|_| SikeError::InvalidEncoding("setup field too large"))
+}
+
+fn encode_strategy(strategy: &Option<Vec<usize>>) -> Result<Vec<u8>, SikeError> {
+    let strategy = match strategy {
+        Some(s) => s,
+        None => return Ok(vec![0]),
+    };
+
+    let mut bytes = vec![1];
+    bytes.extend_from_slice(&encode_len(strategy.len())?);
+    for &s in strategy {
+        bytes.extend_from_slice(&encode_len(s)?);
+    }
+    Ok(bytes)
+}
+
+/// Cursor over the bytes of a blob
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], SikeError> {
+        if self.0.len() < n {
+            return Err(SikeError::InvalidEncoding("truncated setup"));
+        }
+
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn u64(&mut self) -> Result<u64, SikeError> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_be_bytes(bytes))
+    }
+
+    fn len(&mut self) -> Result<usize, SikeError> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(u32::from_be_bytes(bytes) as usize)
+    }
+
+    fn strategy(&mut self) -> Result<Option<Vec<usize>>, SikeError> {
+        match self.take(1)? {
+            [0] => Ok(None),
+            [1] => {
+                let n = self.len()?;
+                // Bound the allocation by the bytes actually present
+                if n > self.0.len() / 4 {
+                    return Err(SikeError::InvalidEncoding("truncated setup"));
+                }
+                (0..n)
+                    .map(|_| self.len())
+                    .collect::<Result<_, _>>()
+                    .map(Some)
+            }
+            _ => Err(SikeError::InvalidEncoding("invalid strategy marker")),
+        }
+    }
+
+    fn point<K: FiniteField>(&mut self) -> Result<K, SikeError> {
+        let n = self.len()?;
+        K::from_bytes(self.take(n)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ff::{PrimeFieldP434, QuadraticExtension},
+        isogeny::sike_p434_params,
+        utils::{shake::ShakeRng, strategy::*},
+    };
+
+    type P434Kem = KEM<QuadraticExtension<PrimeFieldP434>>;
+
+    #[test]
+    fn test_setup_roundtrip_p434() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+        let kem = KEM::setup(params).unwrap();
+
+        let blob = kem.export_setup().unwrap();
+        let imported = KEM::import_setup(&SetupBlob::from_bytes(blob.as_bytes().to_vec())).unwrap();
+        assert_eq!(imported.export_setup().unwrap(), blob);
+
+        // Same seed, same keys
+        let (s, sk3, pk3) = kem.keygen_with_rng(&mut ShakeRng::new(b"setup")).unwrap();
+        let (s_imp, sk3_imp, pk3_imp) = imported
+            .keygen_with_rng(&mut ShakeRng::new(b"setup"))
+            .unwrap();
+        assert_eq!(s, s_imp);
+        assert_eq!(sk3.to_bytes(), sk3_imp.to_bytes());
+        assert_eq!(pk3, pk3_imp);
+
+        let (c, k) = imported.encaps(&pk3).unwrap();
+        assert_eq!(kem.decaps(&s, &sk3, &pk3, c).unwrap(), k);
+
+        // Truncated blobs and malformed strategies are rejected
+        let bytes = blob.as_bytes();
+        let truncated = SetupBlob::from_bytes(bytes[..bytes.len() - 1].to_vec());
+        assert!(P434Kem::import_setup(&truncated).is_err());
+
+        let params = sike_p434_params(None, Some(vec![2; 136])).unwrap();
+        let blob = KEM::setup(params).unwrap().export_setup().unwrap();
+        assert!(matches!(
+            P434Kem::import_setup(&blob),
+            Err(SikeError::InvalidStrategy)
+        ));
+    }
+}
@@ -0,0 +1,150 @@
+//! Timing self-assessment of decapsulation
+//!
+//! **This is a diagnostic, not a guarantee:** the crate is not constant-time (see the crate
+//! documentation), and wall-clock means over a few samples can neither prove nor rule out
+//! a timing leak. It only catches gross differences between the two outcomes of `decaps`.
+
+use super::KEM;
+use crate::{error::SikeError, ff::FiniteField, utils::hash::SikeHash};
+
+use std::{
+    fmt::Debug,
+    time::{Duration, Instant},
+};
+
+/// Relative difference of the means above which `TimingReport::warning` is set
+const TIMING_THRESHOLD: f64 = 0.05;
+
+/// Mean and variance of a series of timings
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimingStats {
+    /// Mean time
+    pub mean: Duration,
+
+    /// Variance, in squared seconds
+    pub variance: f64,
+}
+
+impl TimingStats {
+    fn from_samples(samples: &[Duration]) -> Self {
+        if samples.is_empty() {
+            return Self {
+                mean: Duration::default(),
+                variance: 0.0,
+            };
+        }
+
+        let n = samples.len() as f64;
+        let mean = samples.iter().map(Duration::as_secs_f64).sum::<f64>() / n;
+        let variance = samples
+            .iter()
+            .map(|t| (t.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / n;
+
+        Self {
+            mean: Duration::from_secs_f64(mean),
+            variance,
+        }
+    }
+}
+
+/// Timings of `KEM::timing_self_test`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimingReport {
+    /// Number of decapsulations timed for each kind of ciphertext
+    pub samples: usize,
+
+    /// Decapsulation of valid ciphertexts
+    pub valid: TimingStats,
+
+    /// Decapsulation of ciphertexts failing the re-encryption check
+    pub tampered: TimingStats,
+
+    /// Whether the means differ by more than 5%
+    pub warning: bool,
+}
+
+impl TimingReport {
+    /// Difference of the means, relative to the smaller one
+    pub fn relative_difference(&self) -> f64 {
+        let (valid, tampered) = (
+            self.valid.mean.as_secs_f64(),
+            self.tampered.mean.as_secs_f64(),
+        );
+        let smaller = valid.min(tampered);
+        if smaller == 0.0 {
+            return 0.0;
+        }
+
+        (valid - tampered).abs() / smaller
+    }
+}
+
+impl<K: FiniteField + Clone + Debug, H: SikeHash> KEM<K, H> {
+    /// Time `decaps` on `samples` valid ciphertexts and as many ciphertexts failing the
+    /// re-encryption check, on a fresh keypair
+    ///
+    /// **Diagnostic only:** see the module documentation. Both kinds are interleaved to
+    /// limit the effect of frequency scaling, but results remain noisy.
+    pub fn timing_self_test(&self, samples: usize) -> Result<TimingReport, SikeError> {
+        let (s, sk3, pk3) = self.keygen()?;
+        let (c, _) = self.encaps(&pk3)?;
+
+        // Flipping a bit of c1 changes the decrypted message, so re-encryption fails
+        let mut tampered = c.clone();
+        tampered.bytes1[0] ^= 1;
+
+        let mut valid_times = Vec::with_capacity(samples);
+        let mut tampered_times = Vec::with_capacity(samples);
+        for _ in 0..samples {
+            let start = Instant::now();
+            self.decaps(&s, &sk3, &pk3, c.clone())?;
+            valid_times.push(start.elapsed());
+
+            let start = Instant::now();
+            self.decaps(&s, &sk3, &pk3, tampered.clone())?;
+            tampered_times.push(start.elapsed());
+        }
+
+        let mut report = TimingReport {
+            samples,
+            valid: TimingStats::from_samples(&valid_times),
+            tampered: TimingStats::from_samples(&tampered_times),
+            warning: false,
+        };
+        report.warning = report.relative_difference() > TIMING_THRESHOLD;
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{isogeny::sike_p434_params, utils::strategy::*};
+
+    #[test]
+    fn test_timing_self_test_p434() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+        let kem = KEM::setup(params).unwrap();
+
+        let report = kem.timing_self_test(3).unwrap();
+        assert_eq!(report.samples, 3);
+        assert!(report.valid.mean > Duration::default());
+        assert!(report.tampered.mean > Duration::default());
+        assert!(report.valid.variance >= 0.0 && report.tampered.variance >= 0.0);
+        assert_eq!(
+            report.warning,
+            report.relative_difference() > TIMING_THRESHOLD
+        );
+
+        let stats = TimingStats::from_samples(&[Duration::from_secs(1), Duration::from_secs(3)]);
+        assert_eq!(stats.mean, Duration::from_secs(2));
+        assert!((stats.variance - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,194 @@
+//! Test vectors in the crate's own text format
+//!
+//! Each vector is a block of `key = value` lines, values being hex-encoded:
+//! ```text
+//! count = 0
+//! seed = ...
+//! sk = ...
+//! pk = ...
+//! ct = ...
+//! ss = ...
+//! ```
+//! and blocks are separated by blank lines. Everything is derived from `seed`, so that
+//! vectors can be replayed by a later version of the crate.
+
+use super::KEM;
+use crate::{
+    error::SikeError,
+    ff::FiniteField,
+    pke::Ciphertext,
+    utils::{hash::SikeHash, shake::ShakeRng},
+};
+
+use rand_core::RngCore;
+
+use std::{fmt::Debug, str::FromStr};
+
+/// Seed length, in bytes
+const SEED_LEN: usize = 48;
+
+/// A known-answer test vector
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestVector {
+    /// Seed of the deterministic generator driving keygen and encaps
+    pub seed: Vec<u8>,
+
+    /// Secret key: rejection value `s` followed by `sk3`
+    pub sk: Vec<u8>,
+
+    /// Public key
+    pub pk: Vec<u8>,
+
+    /// Ciphertext
+    pub ct: Vec<u8>,
+
+    /// Shared secret
+    pub ss: Vec<u8>,
+}
+
+impl TestVector {
+    /// Parses the blocks of a file of test vectors
+    pub fn parse_all(s: &str) -> Result<Vec<Self>, SikeError> {
+        s.split("\n\n")
+            .filter(|block| !block.trim().is_empty())
+            .map(Self::from_str)
+            .collect()
+    }
+
+    /// Formats vectors as a file, numbering them in order
+    pub fn format_all(vectors: &[Self]) -> String {
+        vectors
+            .iter()
+            .enumerate()
+            .map(|(count, v)| format!("count = {}\n{}", count, v))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl std::fmt::Display for TestVector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "seed = {}", hex::encode(&self.seed))?;
+        writeln!(f, "sk = {}", hex::encode(&self.sk))?;
+        writeln!(f, "pk = {}", hex::encode(&self.pk))?;
+        writeln!(f, "ct = {}", hex::encode(&self.ct))?;
+        writeln!(f, "ss = {}", hex::encode(&self.ss))
+    }
+}
+
+impl FromStr for TestVector {
+    type Err = SikeError;
+
+    /// Parses a single block, the `count` line is optional and ignored
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let field = |name: &str| -> Result<Vec<u8>, SikeError> {
+            let value = s
+                .lines()
+                .filter_map(|line| line.split_once('='))
+                .find(|(key, _)| key.trim() == name)
+                .map(|(_, value)| value.trim())
+                .ok_or(SikeError::InvalidEncoding("missing test vector field"))?;
+
+            hex::decode(value).map_err(|_| SikeError::InvalidEncoding("invalid hex"))
+        };
+
+        Ok(Self {
+            seed: field("seed")?,
+            sk: field("sk")?,
+            pk: field("pk")?,
+            ct: field("ct")?,
+            ss: field("ss")?,
+        })
+    }
+}
+
+impl<K: FiniteField + Clone + Debug, H: SikeHash> KEM<K, H> {
+    /// Generate `count` test vectors, with seeds drawn from `rng`
+    pub fn generate_test_vectors<R: RngCore>(
+        &self,
+        count: usize,
+        rng: &mut R,
+    ) -> Result<Vec<TestVector>, SikeError> {
+        (0..count)
+            .map(|_| {
+                let mut seed = vec![0; SEED_LEN];
+                rng.fill_bytes(&mut seed);
+                let (vector, _) = self.replay_seed(seed)?;
+                Ok(vector)
+            })
+            .collect()
+    }
+
+    /// Recompute each vector from its seed, and check that decapsulation recovers the shared
+    /// secret
+    ///
+    /// Fails with `TestVectorMismatch` holding the index of the first vector that does not match.
+    pub fn check_test_vectors(&self, vectors: &[TestVector]) -> Result<(), SikeError> {
+        for (i, vector) in vectors.iter().enumerate() {
+            let (expected, recovered) = self.replay_seed(vector.seed.clone())?;
+            if expected != *vector || !recovered {
+                return Err(SikeError::TestVectorMismatch(i));
+            }
+        }
+        Ok(())
+    }
+
+    /// Derive the vector of `seed`, and whether decapsulation recovers its shared secret
+    fn replay_seed(&self, seed: Vec<u8>) -> Result<(TestVector, bool), SikeError> {
+        let mut rng = ShakeRng::new(&seed);
+
+        let (s, sk3, pk3) = self.keygen_with_rng(&mut rng)?;
+        let (c, ss) = self.encaps_with_rng(&pk3, &mut rng)?;
+
+        let recovered = self.decaps(&s, &sk3, &pk3, c.clone())? == ss;
+
+        let vector = TestVector {
+            seed,
+            sk: [&s[..], &sk3.to_bytes()].concat(),
+            pk: Self::public_key_bytes(pk3),
+            ct: Self::ciphertext_bytes(c),
+            ss,
+        };
+
+        Ok((vector, recovered))
+    }
+
+    fn ciphertext_bytes(c: Ciphertext) -> Vec<u8> {
+        [c.bytes00, c.bytes01, c.bytes02, c.bytes1].concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{isogeny::sike_p434_params, utils::strategy::*};
+
+    #[test]
+    fn test_vectors_roundtrip_p434() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+        let kem = KEM::setup(params).unwrap();
+
+        let mut rng = ShakeRng::new(b"test vectors");
+        let vectors = kem.generate_test_vectors(2, &mut rng).unwrap();
+
+        let text = TestVector::format_all(&vectors);
+        let parsed = TestVector::parse_all(&text).unwrap();
+        assert_eq!(parsed, vectors);
+        assert!(kem.check_test_vectors(&parsed).is_ok());
+
+        // Seeds fully determine the vectors
+        let mut rng = ShakeRng::new(b"test vectors");
+        assert_eq!(kem.generate_test_vectors(2, &mut rng).unwrap(), vectors);
+
+        let mut corrupted = parsed;
+        corrupted[1].ss[0] ^= 1;
+        assert!(matches!(
+            kem.check_test_vectors(&corrupted),
+            Err(SikeError::TestVectorMismatch(1))
+        ));
+    }
+}
@@ -10,34 +10,52 @@
 //!
 //! ```rust
 //! use rust_sike::{self, KEM};
-//! let params = rust_sike::sike_p434_params(None, None);
+//! let params = rust_sike::sike_p434_params(None, None).unwrap();
 //!
-//! let kem = KEM::setup(params);
+//! let kem = KEM::setup(params).unwrap();
 //!
 //! // Alice runs keygen, publishes pk3. Values s and sk3 are secret
-//! let (s, sk3, pk3) = kem.keygen();
+//! let (s, sk3, pk3) = kem.keygen().unwrap();
 //!
 //! // Bob uses pk3 to derive a key k and encapsulation c
-//! let (c, k) = kem.encaps(&pk3);
+//! let (c, k) = kem.encaps(&pk3).unwrap();
 //!
 //! // Bob sends c to Alice
 //! // Alice uses s, c, sk3 and pk3 to recover k
-//! let k_recovered = kem.decaps(&s, &sk3, &pk3, c);
+//! let k_recovered = kem.decaps(&s, &sk3, &pk3, c).unwrap();
 //!
 //! assert_eq!(k, k_recovered);
 //! ```
+//!
+//! # Side channels
+//!
+//! Only the following operations on secret data run in constant time:
+//!  * the re-encryption check of `KEM::decaps`, which compares the public keys with
+//!    `subtle::ConstantTimeEq` and selects between `m` and `s` with
+//!    `subtle::ConditionallySelectable`;
+//!  * the tag comparison of `PKE::dec_authenticated`.
+//!
+//! Everything else should be assumed variable-time: field arithmetic relies on GMP (through
+//! `rug`), whose inversion and reduction are not constant-time, and the three-point ladder
+//! branches on the bits of the secret key. This crate is not hardened against timing attacks.
 
 #![warn(missing_docs)]
 #![deny(clippy::mem_forget)]
 #[forbid(unsafe_code)]
 mod constants;
+mod error;
 mod ff;
 mod isogeny;
 mod utils;
 
+pub mod interop;
 pub mod kem;
 pub mod pke;
-pub use {kem::KEM, pke::PKE};
+pub use {
+    error::SikeError,
+    kem::{smoke_test, KEM},
+    pke::PKE,
+};
 
 pub use utils::strategy::{
     compute_strategy, P434_THREE_TORSION_STRATEGY, P434_TWO_TORSION_STRATEGY,
@@ -46,6 +64,17 @@ pub use utils::strategy::{
 };
 
 pub use crate::{
-    isogeny::{sike_p434_params, sike_p503_params, sike_p610_params, sike_p751_params},
-    utils::strategy,
+    isogeny::{
+        detect_parameter_set, sike_p434_params, sike_p503_params, sike_p610_params,
+        sike_p751_params, CurveIsogenies, FixedPublicKey, FixedPublicKeyP434, FixedPublicKeyP503,
+        FixedPublicKeyP610, FixedPublicKeyP751, PreparedPublicKey, ProjectivePublicKey, PublicKey2,
+        PublicKey3, SecretKey, SikeParameterSet, ThreeIsogenyWalk, TwoIsogenyWalk, WalkBenchReport,
+    },
+    utils::{
+        hash::{Shake256Hash, SikeHash},
+        strategy,
+    },
 };
+
+#[cfg(feature = "reference")]
+pub use crate::isogeny::affine_reference;
@@ -0,0 +1,91 @@
+//! Encryption under a reused ephemeral key, for research on key reuse
+//!
+//! **INSECURE:** SIKE is broken by adaptive attacks on reused keys, and two ciphertexts
+//! under the same ephemeral key reveal the XOR of their messages. This is only meant for
+//! experiments, never for production.
+
+use super::{xor, Ciphertext, Message, PublicKey, SecretKey, PKE};
+use crate::{error::SikeError, ff::FiniteField, utils::hash::SikeHash};
+
+use zeroize::Zeroize;
+
+use std::fmt::Debug;
+
+/// Ephemeral key walked once, see `PKE::precompute_ephemeral`
+///
+/// Holds `c0` and the mask `h` derived from the shared j-invariant, which is secret and
+/// zeroed on drop.
+pub struct EphemeralEnc {
+    bytes00: Vec<u8>,
+    bytes01: Vec<u8>,
+    bytes02: Vec<u8>,
+    h: Vec<u8>,
+}
+
+impl<K: FiniteField + Clone + Debug, H: SikeHash> PKE<K, H> {
+    /// Generate an ephemeral key and compute its exchange with `pk`, to encrypt several
+    /// messages with `EphemeralEnc::enc`
+    ///
+    /// **INSECURE:** reusing an ephemeral key breaks the security of the PKE, this is for
+    /// research on key reuse only.
+    pub fn precompute_ephemeral(&self, pk: &PublicKey<K>) -> Result<EphemeralEnc, SikeError> {
+        let (ephemeral, _) = self.ephemeral_with_j(pk)?;
+        Ok(ephemeral)
+    }
+
+    /// Steps 4 to 7 of the encryption, also returning the shared j-invariant
+    pub(super) fn ephemeral_with_j(
+        &self,
+        pk: &PublicKey<K>,
+    ) -> Result<(EphemeralEnc, K), SikeError> {
+        // 4.
+        let sk2 = SecretKey::get_random_secret_key(self.params.keyspace2 as usize)?;
+
+        // 5.
+        let c0: PublicKey<K> = self.isogenies.isogen2_untyped(&sk2)?;
+
+        // 6.
+        let j = self.isogenies.isoex2_untyped(&sk2, pk)?;
+
+        // 7.
+        let h = self.hash_function_f(j.clone())?;
+
+        let (bytes00, bytes01, bytes02) = c0.into_bytes();
+        let ephemeral = EphemeralEnc {
+            bytes00,
+            bytes01,
+            bytes02,
+            h,
+        };
+
+        Ok((ephemeral, j))
+    }
+}
+
+impl EphemeralEnc {
+    /// Encrypt a message under the ephemeral key, all ciphertexts share the same `c0`
+    ///
+    /// **INSECURE:** see `PKE::precompute_ephemeral`.
+    pub fn enc(&self, m: Message) -> Result<Ciphertext, SikeError> {
+        // 8.
+        if self.h.len() != m.bytes.len() {
+            return Err(SikeError::IncorrectHash);
+        }
+
+        let c1_bytes = xor(&m.bytes, &self.h);
+
+        // 9.
+        Ok(Ciphertext {
+            bytes00: self.bytes00.clone(),
+            bytes01: self.bytes01.clone(),
+            bytes02: self.bytes02.clone(),
+            bytes1: c1_bytes,
+        })
+    }
+}
+
+impl Drop for EphemeralEnc {
+    fn drop(&mut self) {
+        self.h.zeroize();
+    }
+}
@@ -8,36 +8,50 @@
 //! let params = rust_sike::sike_p434_params(
 //!     Some(rust_sike::P434_TWO_TORSION_STRATEGY.to_vec()),
 //!     Some(rust_sike::P434_THREE_TORSION_STRATEGY.to_vec()),
-//! );
+//! )
+//! .unwrap();
 //!
-//! let pke = PKE::setup(params.clone());
+//! let pke = PKE::setup(params.clone()).unwrap();
 //!
 //! // Alice generates a keypair, she publishes her pk
-//! let (sk, pk) = pke.gen();
+//! let (sk, pk) = pke.gen().unwrap();
 //!
 //! // Bob writes a message
 //! let msg = Message::from_bytes(vec![0; params.secparam / 8]);
 //! // Bob encrypts the message using Alice's pk
-//! let ciphertext = pke.enc(&pk, msg.clone());
+//! let ciphertext = pke.enc(&pk, msg.clone()).unwrap();
 //!
 //! // Bob sends the ciphertext to Alice
 //! // Alice decrypts the message using her sk
-//! let msg_recovered = pke.dec(&sk, ciphertext);
+//! let msg_recovered = pke.dec(&sk, ciphertext).unwrap();
 //!
 //! // Alice should correctly recover Bob's plaintext message
-//! assert_eq!(msg_recovered.to_bytes(), msg.to_bytes());
+//! assert_eq!(msg_recovered.into_bytes(), msg.into_bytes());
 //! ```
 
 use crate::{
+    error::SikeError,
     ff::FiniteField,
     isogeny::{CurveIsogenies, PublicParameters},
-    utils::shake,
+    utils::{
+        conversion,
+        hash::{Shake256Hash, SikeHash},
+        shake,
+    },
 };
 
+mod ephemeral;
+
 pub use crate::isogeny::{PublicKey, SecretKey};
+pub use ephemeral::EphemeralEnc;
+
+use subtle::ConstantTimeEq;
 
 use std::fmt::Debug;
 
+/// Domain separation tag for the MAC key derived from the j-invariant
+const MAC_KEY_TAG: &[u8] = b"rust-sike pke mac key";
+
 /// `Message`
 #[derive(Clone)]
 pub struct Message {
@@ -55,6 +69,59 @@ impl Message {
     pub fn into_bytes(self) -> Vec<u8> {
         self.bytes
     }
+
+    /// Build a `Message` of `block_len` bytes from a string
+    ///
+    /// The string is UTF-8 encoded and padded with zeros, so it must fit in the block
+    /// (`PKE::block_len` bytes) and must not contain NUL characters.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use rust_sike::{self, pke::{PKE, Message}};
+    /// let params = rust_sike::sike_p751_params(
+    ///     Some(rust_sike::P751_TWO_TORSION_STRATEGY.to_vec()),
+    ///     Some(rust_sike::P751_THREE_TORSION_STRATEGY.to_vec()),
+    /// )
+    /// .unwrap();
+    /// let block_len = params.secparam / 8;
+    ///
+    /// let pke = PKE::setup(params).unwrap();
+    /// let (sk, pk) = pke.gen().unwrap();
+    ///
+    /// let msg = Message::from_str("Hello, 世界!", block_len).unwrap();
+    /// let ciphertext = pke.enc(&pk, msg).unwrap();
+    /// let msg_recovered = pke.dec(&sk, ciphertext).unwrap();
+    ///
+    /// assert_eq!(msg_recovered.to_string_lossy(), "Hello, 世界!");
+    /// ```
+    pub fn from_str(s: &str, block_len: usize) -> Result<Self, SikeError> {
+        if s.len() > block_len {
+            return Err(SikeError::InvalidMessage(
+                "string does not fit in the block",
+            ));
+        }
+        if s.contains('\0') {
+            return Err(SikeError::InvalidMessage("string contains NUL characters"));
+        }
+
+        let mut bytes = s.as_bytes().to_vec();
+        bytes.resize(block_len, 0);
+
+        Ok(Self { bytes })
+    }
+
+    /// Recover a string from a `Message` built with `from_str`
+    ///
+    /// Padding is removed, and invalid UTF-8 sequences are replaced by `U+FFFD`
+    pub fn to_string_lossy(&self) -> String {
+        let end = self
+            .bytes
+            .iter()
+            .rposition(|&b| b != 0)
+            .map_or(0, |i| i + 1);
+
+        String::from_utf8_lossy(&self.bytes[..end]).into_owned()
+    }
 }
 
 /// `Ciphertext`
@@ -75,31 +142,104 @@ pub struct Ciphertext {
     pub bytes1: Vec<u8>,
 }
 
+/// `Ciphertext` with an authentication tag, see `PKE::enc_authenticated`
+#[derive(Clone)]
+pub struct AuthenticatedCiphertext {
+    /// Ciphertext, as produced by `PKE::enc`
+    pub ciphertext: Ciphertext,
+
+    /// MAC over the ciphertext
+    pub tag: Vec<u8>,
+}
+
 /// Public-key cryptosystem (ref Algorithm 1, Section 1.3.9)
-pub struct PKE<K> {
+///
+/// The hash function F is `H`, SHAKE-256 unless built with `setup_with_hash`.
+pub struct PKE<K, H = Shake256Hash> {
     /// Instance of the SIKE problem for this PKE
     pub isogenies: CurveIsogenies<K>,
     params: PublicParameters<K>,
+    reject_trivial_keys: bool,
+    block_len: usize,
+    coord_len: usize,
+    hash: H,
 }
 
 impl<K: FiniteField + Clone + Debug> PKE<K> {
     /// Initialise cryptosystem with parameters `params`
     #[inline]
-    pub fn setup(params: PublicParameters<K>) -> Self {
-        Self {
+    pub fn setup(params: PublicParameters<K>) -> Result<Self, SikeError> {
+        let block_len = params.secparam / 8;
+        Self::setup_with_block_len(params, block_len)
+    }
+
+    /// Initialise cryptosystem with parameters `params`, for messages of `block_len` bytes
+    ///
+    /// The mask F(j) is the first `block_len` bytes of SHAKE-256 over j, so for the default
+    /// `secparam / 8` this is `setup`. Larger blocks encrypt more in one shot, but the
+    /// `KEM` only accepts the default. `block_len` cannot be smaller than `secparam / 8`.
+    pub fn setup_with_block_len(
+        params: PublicParameters<K>,
+        block_len: usize,
+    ) -> Result<Self, SikeError> {
+        Self::init(params, block_len, Shake256Hash)
+    }
+}
+
+impl<K: FiniteField + Clone + Debug, H: SikeHash> PKE<K, H> {
+    /// Initialise cryptosystem with parameters `params`, with `hash` as F
+    ///
+    /// Only `Shake256Hash` gives the cryptosystem of the specification.
+    pub fn setup_with_hash(params: PublicParameters<K>, hash: H) -> Result<Self, SikeError> {
+        let block_len = params.secparam / 8;
+        Self::init(params, block_len, hash)
+    }
+
+    fn init(params: PublicParameters<K>, block_len: usize, hash: H) -> Result<Self, SikeError> {
+        params.validate()?;
+        if block_len < params.secparam / 8 {
+            return Err(SikeError::InvalidMessage("block shorter than secparam / 8"));
+        }
+
+        // Each public key holds 3 elements of 𝔽ₚ(i), so 6 coordinates over 𝔽ₚ
+        let coord_len = params.public_key_byte_len()? / 6;
+
+        Ok(Self {
             isogenies: CurveIsogenies::init(params.clone()),
             params,
-        }
+            reject_trivial_keys: false,
+            block_len,
+            coord_len,
+            hash,
+        })
+    }
+
+    /// Parameters of the cryptosystem
+    pub fn params(&self) -> &PublicParameters<K> {
+        &self.params
+    }
+
+    /// Length in bytes of the messages, see `setup_with_block_len`
+    pub fn block_len(&self) -> usize {
+        self.block_len
+    }
+
+    /// Make decryption fail with `InvalidPublicKey` when `c0` is trivial (see
+    /// `PublicKey::is_trivial`), off by default
+    ///
+    /// The check costs a j-invariant computation on top of the decryption.
+    pub fn set_reject_trivial_keys(&mut self, reject: bool) {
+        self.reject_trivial_keys = reject;
     }
 
     /// Generate a keypair
     #[inline]
-    pub fn gen(&self) -> Result<(SecretKey, PublicKey<K>), String> {
+    pub fn gen(&self) -> Result<(SecretKey, PublicKey<K>), SikeError> {
         // 1.
         let sk3 = SecretKey::get_random_secret_key(self.params.keyspace3 as usize)?;
 
         // 2.
-        let pk3 = self.isogenies.isogen3(&sk3)?;
+        let pk3 = self.isogenies.isogen3_untyped(&sk3)?;
 
         // 3.
         Ok((sk3, pk3))
@@ -107,73 +247,173 @@ impl<K: FiniteField + Clone + Debug> PKE<K> {
 
     /// Encrypt a message
     #[inline]
-    pub fn enc(&self, pk: &PublicKey<K>, m: Message) -> Result<Ciphertext, String> {
-        // 4.
-        let sk2 = SecretKey::get_random_secret_key(self.params.keyspace2 as usize)?;
-
-        // 5.
-        let c0: PublicKey<K> = self.isogenies.isogen2(&sk2)?;
-
-        // 6.
-        let j = self.isogenies.isoex2(&sk2, &pk)?;
-
-        // 7.
-        let h = self.hash_function_f(j);
+    pub fn enc(&self, pk: &PublicKey<K>, m: Message) -> Result<Ciphertext, SikeError> {
+        let (c, _) = self.enc_with_j(pk, m)?;
+        Ok(c)
+    }
 
-        // 8.
-        if h.len() != m.bytes.len() {
-            return Err(String::from("Incorrect Hash"));
-        }
+    /// Encrypt a message and authenticate the ciphertext (encrypt-then-MAC)
+    ///
+    /// The MAC key is derived from the shared j-invariant, separately from the mask, so
+    /// that any modification of the ciphertext is rejected by `dec_authenticated`.
+    pub fn enc_authenticated(
+        &self,
+        pk: &PublicKey<K>,
+        m: Message,
+    ) -> Result<AuthenticatedCiphertext, SikeError> {
+        let (ciphertext, j) = self.enc_with_j(pk, m)?;
+        let tag = self.mac(j, &ciphertext);
+
+        Ok(AuthenticatedCiphertext { ciphertext, tag })
+    }
 
-        let c1_bytes = Self::xor(&m.bytes, &h);
+    fn enc_with_j(&self, pk: &PublicKey<K>, m: Message) -> Result<(Ciphertext, K), SikeError> {
+        let (ephemeral, j) = self.ephemeral_with_j(pk)?;
+        let c = ephemeral.enc(m)?;
 
-        // 9.
-        let (part1, part2, part3) = c0.into_bytes();
-        Ok(Ciphertext {
-            bytes00: part1,
-            bytes01: part2,
-            bytes02: part3,
-            bytes1: c1_bytes,
-        })
+        Ok((c, j))
     }
 
     /// Decrypts a message
     #[inline]
-    pub fn dec(&self, sk: &SecretKey, c: Ciphertext) -> Result<Message, String> {
+    pub fn dec(&self, sk: &SecretKey, c: Ciphertext) -> Result<Message, SikeError> {
         // 10.
         let c0 = &PublicKey::from_bytes(&c.bytes00, &c.bytes01, &c.bytes02)?;
 
-        let j: K = self.isogenies.isoex3(sk, c0)?;
+        let j: K = self.exchange(sk, c0)?;
+
+        self.unmask(j, &c.bytes1)
+    }
+
+    /// Encrypt a message, returning the ephemeral public key `c0` and the masked message `c1`
+    /// separately rather than as the byte fields of a `Ciphertext`
+    pub fn enc_split(
+        &self,
+        pk: &PublicKey<K>,
+        m: Message,
+    ) -> Result<(PublicKey<K>, Vec<u8>), SikeError> {
+        let c = self.enc(pk, m)?;
+        let c0 = PublicKey::from_bytes(&c.bytes00, &c.bytes01, &c.bytes02)?;
+
+        Ok((c0, c.bytes1))
+    }
 
+    /// Decrypts a message given as the ephemeral public key `c0` and the masked message `c1`,
+    /// as returned by `enc_split`
+    pub fn dec_split(
+        &self,
+        sk: &SecretKey,
+        c0: &PublicKey<K>,
+        c1: &[u8],
+    ) -> Result<Message, SikeError> {
+        let j: K = self.exchange(sk, c0)?;
+
+        self.unmask(j, c1)
+    }
+
+    /// Verify the tag of an authenticated ciphertext, then decrypt it
+    pub fn dec_authenticated(
+        &self,
+        sk: &SecretKey,
+        c: AuthenticatedCiphertext,
+    ) -> Result<Message, SikeError> {
+        let ciphertext = c.ciphertext;
+        let c0 = &PublicKey::from_bytes(
+            &ciphertext.bytes00,
+            &ciphertext.bytes01,
+            &ciphertext.bytes02,
+        )?;
+
+        let j: K = self.exchange(sk, c0)?;
+
+        let tag = self.mac(j.clone(), &ciphertext);
+        if !bool::from(tag.ct_eq(&c.tag)) {
+            return Err(SikeError::AuthenticationFailed);
+        }
+
+        self.unmask(j, &ciphertext.bytes1)
+    }
+
+    /// Shared j-invariant of `sk` and the received `c0`, rejecting keys that do not define a
+    /// curve (see `PublicKey::validate`), and trivial keys if enabled
+    pub(crate) fn exchange(&self, sk: &SecretKey, c0: &PublicKey<K>) -> Result<K, SikeError> {
+        c0.validate()?;
+        if self.reject_trivial_keys && c0.is_trivial(&self.params) {
+            return Err(SikeError::InvalidPublicKey);
+        }
+
+        self.isogenies.isoex3_untyped(sk, c0)
+    }
+
+    /// Recover the message from the shared j-invariant and the masked message `c1`
+    fn unmask(&self, j: K, c1: &[u8]) -> Result<Message, SikeError> {
         // 11.
-        let h = self.hash_function_f(j);
+        let h = self.hash_function_f(j)?;
 
         // 12.
-        if h.len() != c.bytes1.len() {
-            return Err(String::from("Incorrect Hash"));
+        if h.len() != c1.len() {
+            return Err(SikeError::IncorrectHash);
         }
 
-        let m = Self::xor(&h, &c.bytes1);
+        let m = Self::xor(&h, c1);
 
         // 13.
         Ok(Message { bytes: m })
     }
 
-    /// Computes the F function
-    pub fn hash_function_f(&self, j: K) -> Vec<u8> {
-        shake::shake256(&j.into_bytes(), self.params.secparam / 8)
+    /// MAC over the ciphertext, keyed by a sub-key derived from `j`
+    ///
+    /// Each part of the ciphertext is prefixed by its length, so that bytes cannot move from
+    /// one part to the next without changing the tag.
+    fn mac(&self, j: K, c: &Ciphertext) -> Vec<u8> {
+        let n = self.params.secparam / 8;
+        let mut input =
+            shake::shake256(&conversion::concatenate(&[MAC_KEY_TAG, &j.into_bytes()]), n);
+
+        for part in &[&c.bytes00, &c.bytes01, &c.bytes02, &c.bytes1] {
+            input.extend_from_slice(&(part.len() as u64).to_le_bytes());
+            input.extend_from_slice(part);
+        }
+        shake::shake256(&input, n)
     }
 
+    /// Computes the F function, over the encoding of j of the specification (see
+    /// `j_invariant_bytes`)
+    pub fn hash_function_f(&self, j: K) -> Result<Vec<u8>, SikeError> {
+        let mut h = vec![0; self.block_len];
+        self.hash.f(&self.j_invariant_bytes(&j)?, &mut h);
+        Ok(h)
+    }
+
+    /// Encoding of a j-invariant as in the specification, each coordinate over 𝔽ₚ on
+    /// ⌈log₂(p) / 8⌉ little-endian bytes
+    pub(crate) fn j_invariant_bytes(&self, j: &K) -> Result<Vec<u8>, SikeError> {
+        j.to_bytes_le(self.coord_len)
+    }
+
+    /// Hash functions of the cryptosystem
+    pub(crate) fn hash(&self) -> &H {
+        &self.hash
+    }
+}
+
+// Without bounds, so that the XOR can be called for a field that is not nameable
+impl<K, H> PKE<K, H> {
     /// Computes the bitwise XOR between two sequences
     pub fn xor(input1: &[u8], input2: &[u8]) -> Vec<u8> {
-        input1
-            .iter()
-            .zip(input2.iter())
-            .map(|(x, y)| x ^ y)
-            .collect()
+        xor(input1, input2)
     }
 }
 
+/// `PKE::xor`, for the callers that have no `PKE` type at hand
+fn xor(input1: &[u8], input2: &[u8]) -> Vec<u8> {
+    input1
+        .iter()
+        .zip(input2.iter())
+        .map(|(x, y)| x ^ y)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -182,6 +422,269 @@ mod tests {
         utils::strategy::*,
     };
 
+    #[test]
+    fn test_setup_invalid_secparam() {
+        let mut params = sike_p434_params(None, None).unwrap();
+        params.secparam = 130;
+
+        assert!(PKE::setup(params.clone()).is_err());
+        assert!(crate::KEM::setup(params).is_err());
+    }
+
+    #[test]
+    fn test_params_consistency() {
+        assert!(sike_p434_params(None, None).unwrap().validate().is_ok());
+        assert!(sike_p503_params(None, None).unwrap().validate().is_ok());
+        assert!(sike_p610_params(None, None).unwrap().validate().is_ok());
+        assert!(sike_p751_params(None, None).unwrap().validate().is_ok());
+
+        let mut params = sike_p434_params(None, None).unwrap();
+        params.keyspace2 += 1;
+        assert!(matches!(
+            params.validate(),
+            Err(SikeError::InconsistentParameters)
+        ));
+
+        let mut params = sike_p751_params(None, None).unwrap();
+        params.e3 -= 10;
+        assert!(matches!(
+            params.validate(),
+            Err(SikeError::InconsistentParameters)
+        ));
+
+        // Truncated to 32 bits, e3 would pass the key space check
+        let mut params = sike_p434_params(None, None).unwrap();
+        params.e3 += 1 << 32;
+        assert!(matches!(
+            params.validate(),
+            Err(SikeError::InconsistentParameters)
+        ));
+
+        // A key space matching a huge e2 does not make it acceptable
+        let mut params = sike_p434_params(None, None).unwrap();
+        params.e2 = 1 << 40;
+        params.keyspace2 = (params.e2 + 7) / 8;
+        assert!(matches!(
+            params.validate(),
+            Err(SikeError::InconsistentParameters)
+        ));
+    }
+
+    #[test]
+    fn test_params_empty_strategy() {
+        let err = sike_p434_params(Some(vec![]), None).err().unwrap();
+        assert!(matches!(
+            err,
+            SikeError::InvalidStrategyLength {
+                len: 0,
+                expected: 107
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "invalid strategy length: 0 entries, expected 107"
+        );
+
+        assert!(matches!(
+            sike_p434_params(None, Some(vec![1; 137])),
+            Err(SikeError::InvalidStrategyLength {
+                len: 137,
+                expected: 136
+            })
+        ));
+
+        // Parameters modified after loading are checked again at setup
+        let mut params = sike_p434_params(None, None).unwrap();
+        params.e3_strategy = Some(vec![]);
+        assert!(matches!(
+            PKE::setup(params),
+            Err(SikeError::InvalidStrategyLength { .. })
+        ));
+    }
+
+    #[test]
+    fn test_message_from_str() {
+        let msg = Message::from_str("héllo", 16).unwrap();
+        assert_eq!(msg.bytes.len(), 16);
+        assert_eq!(msg.to_string_lossy(), "héllo");
+
+        assert!(Message::from_str("0123456789abcdefg", 16).is_err());
+        assert!(Message::from_str("a\0b", 16).is_err());
+    }
+
+    #[test]
+    fn test_pke_authenticated_p434() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+
+        let pke = PKE::setup(params.clone()).unwrap();
+        let (sk, pk) = pke.gen().unwrap();
+
+        let msg = Message::from_bytes(vec![0x5A; params.secparam / 8]);
+        let ciphertext = pke.enc_authenticated(&pk, msg.clone()).unwrap();
+
+        let msg_recovered = pke.dec_authenticated(&sk, ciphertext.clone()).unwrap();
+        assert_eq!(msg_recovered.into_bytes(), msg.into_bytes());
+
+        // A single flipped bit of the masked message is detected
+        let mut tampered = ciphertext.clone();
+        tampered.ciphertext.bytes1[0] ^= 1;
+        assert!(matches!(
+            pke.dec_authenticated(&sk, tampered),
+            Err(SikeError::AuthenticationFailed)
+        ));
+
+        // So is a flipped bit of the tag
+        let mut tampered = ciphertext;
+        tampered.tag[0] ^= 1;
+        assert!(matches!(
+            pke.dec_authenticated(&sk, tampered),
+            Err(SikeError::AuthenticationFailed)
+        ));
+    }
+
+    #[test]
+    fn test_mac_framing() {
+        let pke = PKE::setup(sike_p434_params(None, None).unwrap()).unwrap();
+        let j = pke.params.xp2.clone();
+
+        let c = Ciphertext {
+            bytes00: vec![1, 2],
+            bytes01: vec![3],
+            bytes02: vec![4],
+            bytes1: vec![5],
+        };
+        let moved = Ciphertext {
+            bytes00: vec![1],
+            bytes01: vec![2, 3],
+            ..c.clone()
+        };
+
+        assert_ne!(pke.mac(j.clone(), &c), pke.mac(j, &moved));
+    }
+
+    #[test]
+    fn test_pke_zero_message_p434() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+
+        let pke = PKE::setup(params.clone()).unwrap();
+        let (sk, pk) = pke.gen().unwrap();
+
+        let zeros = vec![0; params.secparam / 8];
+        let c = pke.enc(&pk, Message::from_bytes(zeros.clone())).unwrap();
+
+        // c1 is the mask itself, with no special case for the zero message
+        let c0 = PublicKey::from_bytes(&c.bytes00, &c.bytes01, &c.bytes02).unwrap();
+        let j = pke.isogenies.isoex3_untyped(&sk, &c0).unwrap();
+        assert_eq!(c.bytes1, pke.hash_function_f(j).unwrap());
+
+        assert_eq!(pke.dec(&sk, c).unwrap().into_bytes(), zeros);
+    }
+
+    #[test]
+    fn test_pke_split_p434() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+
+        let pke = PKE::setup(params.clone()).unwrap();
+        let (sk, pk) = pke.gen().unwrap();
+
+        let msg = Message::from_bytes(vec![0x3C; params.secparam / 8]);
+        let (c0, c1) = pke.enc_split(&pk, msg.clone()).unwrap();
+
+        let recovered = pke.dec_split(&sk, &c0, &c1).unwrap();
+        assert_eq!(recovered.into_bytes(), msg.clone().into_bytes());
+
+        // Reassembled into a `Ciphertext`, the split outputs decrypt with `dec`
+        let (bytes00, bytes01, bytes02) = c0.into_bytes();
+        let c = Ciphertext {
+            bytes00,
+            bytes01,
+            bytes02,
+            bytes1: c1,
+        };
+        assert_eq!(pke.dec(&sk, c).unwrap().into_bytes(), msg.into_bytes());
+    }
+
+    #[test]
+    fn test_pke_ephemeral_reuse_p434() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+
+        let pke = PKE::setup(params.clone()).unwrap();
+        let (sk, pk) = pke.gen().unwrap();
+
+        let ephemeral = pke.precompute_ephemeral(&pk).unwrap();
+        let msg1 = Message::from_bytes(vec![0x11; params.secparam / 8]);
+        let msg2 = Message::from_bytes(vec![0x22; params.secparam / 8]);
+        let c1 = ephemeral.enc(msg1.clone()).unwrap();
+        let c2 = ephemeral.enc(msg2.clone()).unwrap();
+
+        // Both ciphertexts share c0
+        assert_eq!(c1.bytes00, c2.bytes00);
+        assert_eq!(c1.bytes01, c2.bytes01);
+        assert_eq!(c1.bytes02, c2.bytes02);
+
+        assert_eq!(pke.dec(&sk, c1).unwrap().into_bytes(), msg1.into_bytes());
+        assert_eq!(pke.dec(&sk, c2).unwrap().into_bytes(), msg2.into_bytes());
+
+        let short = Message::from_bytes(vec![0; params.secparam / 8 - 1]);
+        assert!(ephemeral.enc(short).is_err());
+    }
+
+    #[test]
+    fn test_pke_block_len_p434() {
+        let params = sike_p434_params(
+            Some(P434_TWO_TORSION_STRATEGY.to_vec()),
+            Some(P434_THREE_TORSION_STRATEGY.to_vec()),
+        )
+        .unwrap();
+
+        // 1024-bit blocks
+        let pke = PKE::setup_with_block_len(params.clone(), 128).unwrap();
+        assert_eq!(pke.block_len(), 128);
+        let (sk, pk) = pke.gen().unwrap();
+
+        let msg = Message::from_bytes((0..128).collect());
+        let c = pke.enc(&pk, msg.clone()).unwrap();
+        assert_eq!(c.bytes1.len(), 128);
+        assert_eq!(pke.dec(&sk, c).unwrap().into_bytes(), msg.into_bytes());
+
+        // The default mask is a prefix of the longer one
+        let j = PKE::setup(params.clone())
+            .unwrap()
+            .isogenies
+            .isoex3_untyped(&sk, &pk)
+            .unwrap();
+        let default_mask = PKE::setup(params.clone())
+            .unwrap()
+            .hash_function_f(j.clone())
+            .unwrap();
+        assert_eq!(default_mask[..], pke.hash_function_f(j).unwrap()[..16]);
+
+        assert!(pke
+            .enc(&pk, Message::from_bytes(vec![0; params.secparam / 8]))
+            .is_err());
+        assert!(PKE::setup_with_block_len(params.clone(), params.secparam / 8 - 1).is_err());
+        assert!(matches!(
+            crate::KEM::from_pke(pke, params),
+            Err(SikeError::InconsistentParameters)
+        ));
+    }
+
     #[test]
     fn test_pke_optim_p434() {
         let params = sike_p434_params(
@@ -190,7 +693,7 @@ mod tests {
         )
         .unwrap();
 
-        let pke = PKE::setup(params.clone());
+        let pke = PKE::setup(params.clone()).unwrap();
 
         // Alice generates a keypair, she published her pk
         println!("[Debug] Key generation");
@@ -219,7 +722,7 @@ mod tests {
         )
         .unwrap();
 
-        let pke = PKE::setup(params.clone());
+        let pke = PKE::setup(params.clone()).unwrap();
 
         // Alice generates a keypair, she published her pk
         println!("[Debug] Key generation");
@@ -248,7 +751,7 @@ mod tests {
         )
         .unwrap();
 
-        let pke = PKE::setup(params.clone());
+        let pke = PKE::setup(params.clone()).unwrap();
 
         // Alice generates a keypair, she published her pk
         println!("[Debug] Key generation");
@@ -277,7 +780,7 @@ mod tests {
         )
         .unwrap();
 
-        let pke = PKE::setup(params.clone());
+        let pke = PKE::setup(params.clone()).unwrap();
 
         // Alice generates a keypair, she published her pk
         println!("[Debug] Key generation");
@@ -302,7 +805,7 @@ mod tests {
     fn test_pke_p434() {
         let params = sike_p434_params(None, None).unwrap();
 
-        let pke = PKE::setup(params.clone());
+        let pke = PKE::setup(params.clone()).unwrap();
 
         // Alice generates a keypair, she published her pk
         println!("[Debug] Key generation");
@@ -327,7 +830,7 @@ mod tests {
     fn test_pke_p503() {
         let params = sike_p503_params(None, None).unwrap();
 
-        let pke = PKE::setup(params.clone());
+        let pke = PKE::setup(params.clone()).unwrap();
 
         // Alice generates a keypair, she published her pk
         println!("[Debug] Key generation");
@@ -352,7 +855,7 @@ mod tests {
     fn test_pke_p610() {
         let params = sike_p610_params(None, None).unwrap();
 
-        let pke = PKE::setup(params.clone());
+        let pke = PKE::setup(params.clone()).unwrap();
 
         // Alice generates a keypair, she published her pk
         println!("[Debug] Key generation");
@@ -377,7 +880,7 @@ mod tests {
     fn test_pke_p751() {
         let params = sike_p751_params(None, None).unwrap();
 
-        let pke = PKE::setup(params.clone());
+        let pke = PKE::setup(params.clone()).unwrap();
 
         // Alice generates a keypair, she published her pk
         println!("[Debug] Key generation");
@@ -1,16 +1,24 @@
 //! Utils for conversions
 
-use crate::ff::{
-    PrimeFieldP434, PrimeFieldP503, PrimeFieldP610, PrimeFieldP751, QuadraticExtension,
+use crate::{
+    error::SikeError,
+    ff::{PrimeFieldP434, PrimeFieldP503, PrimeFieldP610, PrimeFieldP751, QuadraticExtension},
 };
 
-/// String to `u64` conversion
-pub fn str_to_u64(s: &str) -> Result<u64, String> {
-    u64::from_str_radix(s, 16).or_else(|_| Err(String::from("Cannot parse from string")))
+/// Hexadecimal string to `u64` conversion
+///
+/// Only unsigned hexadecimal digits are accepted, without the leading `+` that
+/// `u64::from_str_radix` allows. Values above `u64::MAX` fail with `ParseInt`.
+pub fn str_to_u64(s: &str) -> Result<u64, SikeError> {
+    if !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(SikeError::InvalidEncoding("invalid hex"));
+    }
+
+    Ok(u64::from_str_radix(s, 16)?)
 }
 
 /// String to an element of the quadratic extension field conversion
-pub fn str_to_p434(s0: &str, s1: &str) -> Result<QuadraticExtension<PrimeFieldP434>, String> {
+pub fn str_to_p434(s0: &str, s1: &str) -> Result<QuadraticExtension<PrimeFieldP434>, SikeError> {
     Ok(QuadraticExtension::from(
         PrimeFieldP434::from_string(s0)?,
         PrimeFieldP434::from_string(s1)?,
@@ -18,7 +26,7 @@ pub fn str_to_p434(s0: &str, s1: &str) -> Result<QuadraticExtension<PrimeFieldP4
 }
 
 /// String to an element of the quadratic extension field conversion
-pub fn str_to_p503(s0: &str, s1: &str) -> Result<QuadraticExtension<PrimeFieldP503>, String> {
+pub fn str_to_p503(s0: &str, s1: &str) -> Result<QuadraticExtension<PrimeFieldP503>, SikeError> {
     Ok(QuadraticExtension::from(
         PrimeFieldP503::from_string(s0)?,
         PrimeFieldP503::from_string(s1)?,
@@ -26,7 +34,7 @@ pub fn str_to_p503(s0: &str, s1: &str) -> Result<QuadraticExtension<PrimeFieldP5
 }
 
 /// String to an element of the quadratic extension field conversion
-pub fn str_to_p751(s0: &str, s1: &str) -> Result<QuadraticExtension<PrimeFieldP751>, String> {
+pub fn str_to_p751(s0: &str, s1: &str) -> Result<QuadraticExtension<PrimeFieldP751>, SikeError> {
     Ok(QuadraticExtension::from(
         PrimeFieldP751::from_string(s0)?,
         PrimeFieldP751::from_string(s1)?,
@@ -34,7 +42,7 @@ pub fn str_to_p751(s0: &str, s1: &str) -> Result<QuadraticExtension<PrimeFieldP7
 }
 
 /// String to an element of the quadratic extension field conversion
-pub fn str_to_p610(s0: &str, s1: &str) -> Result<QuadraticExtension<PrimeFieldP610>, String> {
+pub fn str_to_p610(s0: &str, s1: &str) -> Result<QuadraticExtension<PrimeFieldP610>, SikeError> {
     Ok(QuadraticExtension::from(
         PrimeFieldP610::from_string(s0)?,
         PrimeFieldP610::from_string(s1)?,
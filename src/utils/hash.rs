@@ -0,0 +1,36 @@
+//! Hash functions F, G and H of the PKE and the KEM
+
+use crate::utils::shake;
+
+/// Hash functions of SIKE, the output length is the length of `out`
+///
+/// The specification instantiates all three with SHAKE-256 (`Shake256Hash`), any other
+/// instantiation is not interoperable and only meant for testing or experiments.
+pub trait SikeHash {
+    /// F, mask of the message derived from the encoding of the shared j-invariant
+    fn f(&self, j: &[u8], out: &mut [u8]);
+
+    /// G, ephemeral secret derived from m ‖ pk
+    fn g(&self, input: &[u8], out: &mut [u8]);
+
+    /// H, shared secret derived from m ‖ c (or s ‖ c on rejection)
+    fn h(&self, input: &[u8], out: &mut [u8]);
+}
+
+/// F, G and H as SHAKE-256, as in the specification
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Shake256Hash;
+
+impl SikeHash for Shake256Hash {
+    fn f(&self, j: &[u8], out: &mut [u8]) {
+        shake::shake256_into(j, out);
+    }
+
+    fn g(&self, input: &[u8], out: &mut [u8]) {
+        shake::shake256_into(input, out);
+    }
+
+    fn h(&self, input: &[u8], out: &mut [u8]) {
+        shake::shake256_into(input, out);
+    }
+}
@@ -1,16 +1,21 @@
 //! Misc utilities
+//!
+//! Encoding, hashing and strategy helpers only: the curve and isogeny arithmetic lives in
+//! `crate::isogeny`, and is not duplicated here.
 
 pub mod conversion;
+pub mod hash;
 pub mod shake;
 pub mod strategy;
 
 #[cfg(test)]
 mod tests {
     use super::{
-        conversion::concatenate,
-        shake::shake256,
-        strategy::{compute_strategy, P434_THREE_TORSION_STRATEGY, P434_TWO_TORSION_STRATEGY},
+        conversion::{concatenate, str_to_u64},
+        shake::{shake256, shake256_reader},
+        strategy::*,
     };
+    use sha3::digest::XofReader;
 
     fn compare_arrays<T>(array1: &[T], array2: &[T]) -> bool
     where
@@ -42,6 +47,22 @@ mod tests {
         assert_eq!(c, d)
     }
 
+    #[test]
+    fn test_str_to_u64() {
+        assert_eq!(str_to_u64("D8").unwrap(), 216);
+        assert_eq!(str_to_u64("ffffffffffffffff").unwrap(), u64::MAX);
+
+        // 17 digits overflow u64
+        assert!(matches!(
+            str_to_u64("10000000000000000"),
+            Err(crate::SikeError::ParseInt(_))
+        ));
+        assert!(str_to_u64("+D8").is_err());
+        assert!(str_to_u64("-1").is_err());
+        assert!(str_to_u64("0x89").is_err());
+        assert!(str_to_u64("").is_err());
+    }
+
     #[test]
     fn test_shake256_0bit() {
         let msg = vec![];
@@ -91,6 +112,20 @@ mod tests {
         assert!(compare_arrays(&reference, &output))
     }
 
+    #[test]
+    fn test_shake256_reader() {
+        let input = b"incremental";
+        let n = 1000;
+
+        let mut reader = shake256_reader(input);
+        let mut output = vec![0; n];
+        for chunk in output.chunks_mut(7) {
+            reader.read(chunk);
+        }
+
+        assert_eq!(output, shake256(input, n));
+    }
+
     #[test]
     fn test_strategy_2tor() {
         let n4 = 107;
@@ -111,4 +146,69 @@ mod tests {
 
         assert!(compare_arrays(&p434strat, &P434_THREE_TORSION_STRATEGY));
     }
+
+    #[test]
+    fn test_strategy_large_weights() {
+        let n = 30;
+        let (p, q) = (5633, 5461);
+
+        // Scaling the weights does not change the optimal strategy, but overflows u64 costs
+        let scale = 1 << 50;
+        let strat = compute_strategy(n, p, q).unwrap();
+        let scaled_strat = compute_strategy(n, p * scale, q * scale).unwrap();
+
+        assert_eq!(scaled_strat.len(), n);
+        assert!(compare_arrays(&strat, &scaled_strat));
+    }
+
+    #[test]
+    fn test_strategy_cost() {
+        let (n4, p4, q4) = (107, 5633, 5461);
+        let (n3, p3, q3) = (136, 5322, 5282);
+
+        // The reference strategies reach the optimal cost
+        assert!(is_optimal(&P434_TWO_TORSION_STRATEGY, n4, p4, q4));
+        assert!(is_optimal(&P434_THREE_TORSION_STRATEGY, n3, p3, q3));
+
+        // Splitting off one leaf at a time is a valid but poor strategy
+        let linear = (1..=n4).rev().collect::<Vec<_>>();
+        let linear_cost = cost(&linear, p4, q4).unwrap();
+        assert!(linear_cost > cost(&P434_TWO_TORSION_STRATEGY, p4, q4).unwrap());
+        assert!(!is_optimal(&linear, n4, p4, q4));
+
+        // Malformed strategies
+        assert!(cost(&[3, 1], p4, q4).is_err());
+        assert!(cost(&[0, 1], p4, q4).is_err());
+        assert!(cost(&[1, 1, 5], p4, q4).is_err());
+        assert!(!is_optimal(&P434_TWO_TORSION_STRATEGY[1..], n4 - 1, p4, q4));
+    }
+
+    #[test]
+    fn test_strategy_shape() {
+        let strategies: [(&[usize], usize); 8] = [
+            (&P434_TWO_TORSION_STRATEGY, 108),
+            (&P434_THREE_TORSION_STRATEGY, 137),
+            (&P503_TWO_TORSION_STRATEGY, 125),
+            (&P503_THREE_TORSION_STRATEGY, 159),
+            (&P610_TWO_TORSION_STRATEGY, 152),
+            (&P610_THREE_TORSION_STRATEGY, 192),
+            (&P751_TWO_TORSION_STRATEGY, 186),
+            (&P751_THREE_TORSION_STRATEGY, 239),
+        ];
+        for (strategy, steps) in strategies.iter() {
+            assert_eq!(leaf_count(strategy), *steps);
+            assert!(validate_shape(strategy, *steps).is_ok());
+            assert!(validate_shape(strategy, steps + 1).is_err());
+        }
+
+        // A truncated strategy encodes fewer steps, and runs out before the last leaf
+        let truncated = &P434_TWO_TORSION_STRATEGY[..106];
+        assert_eq!(leaf_count(truncated), 107);
+        assert!(validate_shape(truncated, 108).is_err());
+
+        // Invalid splits stop the traversal
+        assert_eq!(leaf_count(&[0, 1]), 0);
+        assert!(validate_shape(&[3, 1], 3).is_err());
+        assert!(validate_shape(&[], 1).is_ok());
+    }
 }
@@ -1,5 +1,6 @@
 //! Utils for SHAKE
 
+use rand_core::{impls, Error, RngCore};
 use sha3::digest::{ExtendableOutput, Update, XofReader};
 use sha3::Shake256;
 
@@ -15,8 +16,58 @@ use sha3::Shake256;
 #[inline]
 pub fn shake256(input: &[u8], len: usize) -> Vec<u8> {
     let mut buffer = vec![0; len];
+    shake256_into(input, &mut buffer);
+    buffer
+}
+
+/// SHAKE-256 wrapper writing into an existing buffer
+///   * Input: `input` string and `output` buffer, filled entirely
+#[inline]
+pub fn shake256_into(input: &[u8], output: &mut [u8]) {
+    shake256_reader(input).read(output);
+}
+
+/// SHAKE-256 output stream of `input`, to be read incrementally
+///
+/// Reading `n` bytes in any number of calls gives `shake256(input, n)`.
+#[inline]
+pub fn shake256_reader(input: &[u8]) -> impl XofReader {
     let mut shake = Shake256::default();
     shake.update(input);
-    shake.finalize_xof().read(&mut buffer);
-    buffer
+    shake.finalize_xof()
+}
+
+/// Deterministic byte stream read from SHAKE-256, for use where an `RngCore` is expected
+pub struct ShakeRng {
+    reader: <Shake256 as ExtendableOutput>::Reader,
+}
+
+impl ShakeRng {
+    /// Absorbs `seed` and returns a reader on the output stream
+    pub fn new(seed: &[u8]) -> Self {
+        let mut shake = Shake256::default();
+        shake.update(seed);
+        Self {
+            reader: shake.finalize_xof(),
+        }
+    }
+}
+
+impl RngCore for ShakeRng {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.reader.read(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
 }
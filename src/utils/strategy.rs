@@ -1,5 +1,7 @@
 //! Utils for tree traversal strategies
 
+use crate::error::SikeError;
+
 /// 2-torsion tree-traversal strategy
 pub type Torsion2Strategy = Vec<usize>;
 
@@ -93,15 +95,17 @@ pub const P751_THREE_TORSION_STRATEGY: [usize; 238] = [
 /// let strat = compute_strategy(12, 13, 14);
 /// println!("{:?}", strat);
 /// ```
-pub fn compute_strategy(n: usize, p: u64, q: u64) -> Result<Vec<usize>, &'static str> {
+pub fn compute_strategy(n: usize, p: u64, q: u64) -> Result<Vec<usize>, SikeError> {
     // 1.
     let mut strategies = vec![vec![]];
 
     // 2.
-    let mut cost = vec![0];
+    // Costs are accumulated in u128 so that large weights cannot overflow
+    let mut cost: Vec<u128> = vec![0];
+    let (p, q) = (u128::from(p), u128::from(q));
 
-    let eval = |c: &Vec<u64>, i: u64, b: u64| {
-        c[(i - b) as usize - 1] + c[b as usize - 1] + b * p + (i - b) * q
+    let eval = |c: &Vec<u128>, i: u64, b: u64| {
+        c[(i - b) as usize - 1] + c[b as usize - 1] + u128::from(b) * p + u128::from(i - b) * q
     };
 
     // 3.
@@ -130,6 +134,150 @@ pub fn compute_strategy(n: usize, p: u64, q: u64) -> Result<Vec<usize>, &'static
     // 7.
     match strategies.last() {
         Some(s) => Ok(s.to_vec()),
-        None => Err("No valid strategy found"),
+        None => Err(SikeError::InvalidStrategy),
+    }
+}
+
+/// Total cost of a strategy, as measured by `compute_strategy`
+///   * Input: strategy, parameters `p`, `q`
+///   * Output: cost of the traversal of the tree of `strategy.len() + 1` leaves
+///
+/// Fails with `InvalidStrategy` if `strategy` does not describe a tree.
+pub fn cost(strategy: &[usize], p: u64, q: u64) -> Result<u128, SikeError> {
+    let mut rest = strategy;
+    let total = subtree_cost(&mut rest, strategy.len() + 1, u128::from(p), u128::from(q))?;
+
+    match rest {
+        [] => Ok(total),
+        _ => Err(SikeError::InvalidStrategy),
+    }
+}
+
+/// Cost of the subtree of `leaves` leaves at the start of `strategy`, which is advanced
+/// past it (the split `b`, then the subtrees of `leaves - b` and `b` leaves)
+fn subtree_cost(
+    strategy: &mut &[usize],
+    leaves: usize,
+    p: u128,
+    q: u128,
+) -> Result<u128, SikeError> {
+    if leaves == 1 {
+        return Ok(0);
+    }
+
+    let (&b, rest) = strategy.split_first().ok_or(SikeError::InvalidStrategy)?;
+    if b == 0 || b >= leaves {
+        return Err(SikeError::InvalidStrategy);
+    }
+    *strategy = rest;
+
+    let left = subtree_cost(strategy, leaves - b, p, q)?;
+    let right = subtree_cost(strategy, b, p, q)?;
+
+    Ok(left + right + b as u128 * p + (leaves - b) as u128 * q)
+}
+
+/// Checks whether a strategy of size `n` has the cost of the one found by `compute_strategy`
+///
+/// Several strategies can have the optimal cost, so the strategy itself may differ.
+pub fn is_optimal(strategy: &[usize], n: usize, p: u64, q: u64) -> bool {
+    if strategy.len() != n {
+        return false;
+    }
+
+    let optimal_cost = compute_strategy(n, p, q).and_then(|optimal| cost(&optimal, p, q));
+
+    match (cost(strategy, p, q), optimal_cost) {
+        (Ok(c), Ok(c_opt)) => c <= c_opt,
+        _ => false,
     }
 }
+
+/// Number of leaves reached by the tree traversal of `strategy`
+///
+/// The walk is simulated on a tree of `strategy.len() + 1` leaves, the size a well-formed
+/// strategy describes. It stops early on an invalid split, or when the strategy runs out,
+/// so that a result below `strategy.len() + 1` points at a malformed strategy.
+pub fn leaf_count(strategy: &[usize]) -> usize {
+    traverse(strategy, strategy.len() + 1).leaves
+}
+
+/// Checks that `strategy` has the `expected_steps - 1` entries of a tree of `expected_steps`
+/// leaves
+///
+/// Fails with `InvalidStrategyLength` otherwise, e.g. for an empty strategy.
+pub fn check_len(strategy: &[usize], expected_steps: usize) -> Result<(), SikeError> {
+    let expected = expected_steps.saturating_sub(1);
+    if strategy.len() == expected {
+        Ok(())
+    } else {
+        Err(SikeError::InvalidStrategyLength {
+            len: strategy.len(),
+            expected,
+        })
+    }
+}
+
+/// Checks that the tree traversal of `strategy` performs exactly `expected_steps` isogeny
+/// steps, consuming the whole strategy
+///
+/// Fails with `InvalidStrategyLength` if `strategy` has the wrong length (see `check_len`),
+/// and with `InvalidStrategy` otherwise.
+pub fn validate_shape(strategy: &[usize], expected_steps: usize) -> Result<(), SikeError> {
+    check_len(strategy, expected_steps)?;
+    let traversal = traverse(strategy, expected_steps);
+
+    if traversal.complete
+        && traversal.consumed == strategy.len()
+        && traversal.leaves == expected_steps
+    {
+        Ok(())
+    } else {
+        Err(SikeError::InvalidStrategy)
+    }
+}
+
+/// Outcome of the simulated traversal of a strategy
+struct Traversal {
+    /// Leaves reached, i.e. isogeny steps performed
+    leaves: usize,
+
+    /// Elements of the strategy read
+    consumed: usize,
+
+    /// Whether the walk ran to its end
+    complete: bool,
+}
+
+/// Simulates the queue of the optimised walks (ref Algorithms 19 and 20), on heights only
+fn traverse(strategy: &[usize], height: usize) -> Traversal {
+    let mut traversal = Traversal {
+        leaves: 0,
+        consumed: 0,
+        complete: false,
+    };
+    if height == 0 {
+        return traversal;
+    }
+
+    let mut queue = vec![height];
+    while let Some(h) = queue.pop() {
+        if h == 1 {
+            traversal.leaves += 1;
+            queue.iter_mut().for_each(|h_prime| *h_prime -= 1);
+            continue;
+        }
+
+        match strategy.get(traversal.consumed) {
+            Some(&s) if s > 0 && h > s => {
+                queue.push(h);
+                queue.push(h - s);
+                traversal.consumed += 1;
+            }
+            _ => return traversal,
+        }
+    }
+
+    traversal.complete = true;
+    traversal
+}